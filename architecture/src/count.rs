@@ -1,3 +1,7 @@
 pub const REGISTERS_COUNT: usize = 256;
 pub const LOCKS_COUNT: usize = 64;
 pub const THREADS_COUNT: usize = 16;
+pub const SEMAPHORES_COUNT: usize = 64;
+pub const BARRIERS_COUNT: usize = 16;
+pub const CHANNELS_COUNT: usize = 16;
+pub const CHANNEL_CAPACITY: usize = 8;
@@ -1,5 +1,5 @@
 mod count;
 mod opcode;
 
-pub use count::{ REGISTERS_COUNT, LOCKS_COUNT, THREADS_COUNT };
+pub use count::{ REGISTERS_COUNT, LOCKS_COUNT, THREADS_COUNT, SEMAPHORES_COUNT, BARRIERS_COUNT, CHANNELS_COUNT, CHANNEL_CAPACITY };
 pub use opcode::Opcode;
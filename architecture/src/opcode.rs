@@ -1,4 +1,4 @@
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Hash)]
 pub enum Opcode {
     Nop,
     Move,
@@ -40,6 +40,128 @@ pub enum Opcode {
     ProfileReset,
     ProfileDump,
     End,
+    SDiv,
+    SRem,
+    SLt,
+    SGt,
+    ShiftRA,
+    Load8S,
+    Load16S,
+    Load32S,
+    AddC,
+    SubC,
+    MulC,
+    MulH,
+    Not,
+    AndN,
+    RotL,
+    RotR,
+    PopCnt,
+    Clz,
+    Ctz,
+    BSwap,
+    BitExtract,
+    BitInsert,
+    Ne,
+    Le,
+    Ge,
+    Select,
+    AndI8,
+    AndI16,
+    AndI32,
+    OrI8,
+    OrI16,
+    OrI32,
+    XorI8,
+    XorI16,
+    XorI32,
+    ShiftLI8,
+    ShiftLI16,
+    ShiftLI32,
+    ShiftRI8,
+    ShiftRI16,
+    ShiftRI32,
+    ShiftRAI8,
+    ShiftRAI16,
+    ShiftRAI32,
+    AddI8,
+    AddI16,
+    AddI32,
+    SubI8,
+    SubI16,
+    SubI32,
+    MulI8,
+    MulI16,
+    MulI32,
+    Load8O,
+    Load16O,
+    Load32O,
+    Load64O,
+    Load8SO,
+    Load16SO,
+    Load32SO,
+    Store8O,
+    Store16O,
+    Store32O,
+    Store64O,
+    JumpI,
+    JumpIfI,
+    StartI,
+    Br8,
+    Br16,
+    BrIf8,
+    BrIf16,
+    Call,
+    CallI,
+    Ret,
+    Push,
+    Pop,
+    FAdd,
+    FSub,
+    FMul,
+    FDiv,
+    FSqrt,
+    FMin,
+    FMax,
+    FEq,
+    FLt,
+    FGt,
+    FLe,
+    FGe,
+    IToF,
+    FToI,
+    FPrint,
+    VAdd,
+    VSub,
+    VMul,
+    VAnd,
+    VOr,
+    VXor,
+    VFAdd,
+    VFSub,
+    VFMul,
+    VFDiv,
+    VLoad,
+    VStore,
+    Cas,
+    Xchg,
+    FetchAdd,
+    FetchAnd,
+    FetchOr,
+    SemInit,
+    SemUp,
+    SemDown,
+    Barrier,
+    WaitAll,
+    WaitAny,
+    Send,
+    Recv,
+    TrySend,
+    TryRecv,
+    Tid,
+    TStatus,
+    Join,
+    Spawn,
 }
 
 impl Opcode {
@@ -85,6 +207,128 @@ impl Opcode {
             0x25 => Opcode::ProfileReset,
             0x26 => Opcode::ProfileDump,
             0x27 => Opcode::End,
+            0x28 => Opcode::SDiv,
+            0x29 => Opcode::SRem,
+            0x2A => Opcode::SLt,
+            0x2B => Opcode::SGt,
+            0x2C => Opcode::ShiftRA,
+            0x2D => Opcode::Load8S,
+            0x2E => Opcode::Load16S,
+            0x2F => Opcode::Load32S,
+            0x30 => Opcode::AddC,
+            0x31 => Opcode::SubC,
+            0x32 => Opcode::MulC,
+            0x33 => Opcode::MulH,
+            0x34 => Opcode::Not,
+            0x35 => Opcode::AndN,
+            0x36 => Opcode::RotL,
+            0x37 => Opcode::RotR,
+            0x38 => Opcode::PopCnt,
+            0x39 => Opcode::Clz,
+            0x3A => Opcode::Ctz,
+            0x3B => Opcode::BSwap,
+            0x3C => Opcode::BitExtract,
+            0x3D => Opcode::BitInsert,
+            0x3E => Opcode::Ne,
+            0x3F => Opcode::Le,
+            0x40 => Opcode::Ge,
+            0x41 => Opcode::Select,
+            0x42 => Opcode::AndI8,
+            0x43 => Opcode::AndI16,
+            0x44 => Opcode::AndI32,
+            0x45 => Opcode::OrI8,
+            0x46 => Opcode::OrI16,
+            0x47 => Opcode::OrI32,
+            0x48 => Opcode::XorI8,
+            0x49 => Opcode::XorI16,
+            0x4A => Opcode::XorI32,
+            0x4B => Opcode::ShiftLI8,
+            0x4C => Opcode::ShiftLI16,
+            0x4D => Opcode::ShiftLI32,
+            0x4E => Opcode::ShiftRI8,
+            0x4F => Opcode::ShiftRI16,
+            0x50 => Opcode::ShiftRI32,
+            0x51 => Opcode::ShiftRAI8,
+            0x52 => Opcode::ShiftRAI16,
+            0x53 => Opcode::ShiftRAI32,
+            0x54 => Opcode::AddI8,
+            0x55 => Opcode::AddI16,
+            0x56 => Opcode::AddI32,
+            0x57 => Opcode::SubI8,
+            0x58 => Opcode::SubI16,
+            0x59 => Opcode::SubI32,
+            0x5A => Opcode::MulI8,
+            0x5B => Opcode::MulI16,
+            0x5C => Opcode::MulI32,
+            0x5D => Opcode::Load8O,
+            0x5E => Opcode::Load16O,
+            0x5F => Opcode::Load32O,
+            0x60 => Opcode::Load64O,
+            0x61 => Opcode::Load8SO,
+            0x62 => Opcode::Load16SO,
+            0x63 => Opcode::Load32SO,
+            0x64 => Opcode::Store8O,
+            0x65 => Opcode::Store16O,
+            0x66 => Opcode::Store32O,
+            0x67 => Opcode::Store64O,
+            0x68 => Opcode::JumpI,
+            0x69 => Opcode::JumpIfI,
+            0x6A => Opcode::StartI,
+            0x6B => Opcode::Br8,
+            0x6C => Opcode::Br16,
+            0x6D => Opcode::BrIf8,
+            0x6E => Opcode::BrIf16,
+            0x6F => Opcode::Call,
+            0x70 => Opcode::CallI,
+            0x71 => Opcode::Ret,
+            0x72 => Opcode::Push,
+            0x73 => Opcode::Pop,
+            0x74 => Opcode::FAdd,
+            0x75 => Opcode::FSub,
+            0x76 => Opcode::FMul,
+            0x77 => Opcode::FDiv,
+            0x78 => Opcode::FSqrt,
+            0x79 => Opcode::FMin,
+            0x7A => Opcode::FMax,
+            0x7B => Opcode::FEq,
+            0x7C => Opcode::FLt,
+            0x7D => Opcode::FGt,
+            0x7E => Opcode::FLe,
+            0x7F => Opcode::FGe,
+            0x80 => Opcode::IToF,
+            0x81 => Opcode::FToI,
+            0x82 => Opcode::FPrint,
+            0x83 => Opcode::VAdd,
+            0x84 => Opcode::VSub,
+            0x85 => Opcode::VMul,
+            0x86 => Opcode::VAnd,
+            0x87 => Opcode::VOr,
+            0x88 => Opcode::VXor,
+            0x89 => Opcode::VFAdd,
+            0x8A => Opcode::VFSub,
+            0x8B => Opcode::VFMul,
+            0x8C => Opcode::VFDiv,
+            0x8D => Opcode::VLoad,
+            0x8E => Opcode::VStore,
+            0x8F => Opcode::Cas,
+            0x90 => Opcode::Xchg,
+            0x91 => Opcode::FetchAdd,
+            0x92 => Opcode::FetchAnd,
+            0x93 => Opcode::FetchOr,
+            0x94 => Opcode::SemInit,
+            0x95 => Opcode::SemUp,
+            0x96 => Opcode::SemDown,
+            0x97 => Opcode::Barrier,
+            0x98 => Opcode::WaitAll,
+            0x99 => Opcode::WaitAny,
+            0x9A => Opcode::Send,
+            0x9B => Opcode::Recv,
+            0x9C => Opcode::TrySend,
+            0x9D => Opcode::TryRecv,
+            0x9E => Opcode::Tid,
+            0x9F => Opcode::TStatus,
+            0xA0 => Opcode::Join,
+            0xA1 => Opcode::Spawn,
             _ => return None,
         })
     }
@@ -131,6 +375,128 @@ impl Opcode {
             Opcode::ProfileReset => 0x25,
             Opcode::ProfileDump  => 0x26,
             Opcode::End          => 0x27,
+            Opcode::SDiv         => 0x28,
+            Opcode::SRem         => 0x29,
+            Opcode::SLt          => 0x2A,
+            Opcode::SGt          => 0x2B,
+            Opcode::ShiftRA      => 0x2C,
+            Opcode::Load8S       => 0x2D,
+            Opcode::Load16S      => 0x2E,
+            Opcode::Load32S      => 0x2F,
+            Opcode::AddC         => 0x30,
+            Opcode::SubC         => 0x31,
+            Opcode::MulC         => 0x32,
+            Opcode::MulH         => 0x33,
+            Opcode::Not          => 0x34,
+            Opcode::AndN         => 0x35,
+            Opcode::RotL         => 0x36,
+            Opcode::RotR         => 0x37,
+            Opcode::PopCnt       => 0x38,
+            Opcode::Clz          => 0x39,
+            Opcode::Ctz          => 0x3A,
+            Opcode::BSwap        => 0x3B,
+            Opcode::BitExtract   => 0x3C,
+            Opcode::BitInsert    => 0x3D,
+            Opcode::Ne           => 0x3E,
+            Opcode::Le           => 0x3F,
+            Opcode::Ge           => 0x40,
+            Opcode::Select       => 0x41,
+            Opcode::AndI8        => 0x42,
+            Opcode::AndI16       => 0x43,
+            Opcode::AndI32       => 0x44,
+            Opcode::OrI8         => 0x45,
+            Opcode::OrI16        => 0x46,
+            Opcode::OrI32        => 0x47,
+            Opcode::XorI8        => 0x48,
+            Opcode::XorI16       => 0x49,
+            Opcode::XorI32       => 0x4A,
+            Opcode::ShiftLI8     => 0x4B,
+            Opcode::ShiftLI16    => 0x4C,
+            Opcode::ShiftLI32    => 0x4D,
+            Opcode::ShiftRI8     => 0x4E,
+            Opcode::ShiftRI16    => 0x4F,
+            Opcode::ShiftRI32    => 0x50,
+            Opcode::ShiftRAI8    => 0x51,
+            Opcode::ShiftRAI16   => 0x52,
+            Opcode::ShiftRAI32   => 0x53,
+            Opcode::AddI8        => 0x54,
+            Opcode::AddI16       => 0x55,
+            Opcode::AddI32       => 0x56,
+            Opcode::SubI8        => 0x57,
+            Opcode::SubI16       => 0x58,
+            Opcode::SubI32       => 0x59,
+            Opcode::MulI8        => 0x5A,
+            Opcode::MulI16       => 0x5B,
+            Opcode::MulI32       => 0x5C,
+            Opcode::Load8O       => 0x5D,
+            Opcode::Load16O      => 0x5E,
+            Opcode::Load32O      => 0x5F,
+            Opcode::Load64O      => 0x60,
+            Opcode::Load8SO      => 0x61,
+            Opcode::Load16SO     => 0x62,
+            Opcode::Load32SO     => 0x63,
+            Opcode::Store8O      => 0x64,
+            Opcode::Store16O     => 0x65,
+            Opcode::Store32O     => 0x66,
+            Opcode::Store64O     => 0x67,
+            Opcode::JumpI        => 0x68,
+            Opcode::JumpIfI      => 0x69,
+            Opcode::StartI       => 0x6A,
+            Opcode::Br8          => 0x6B,
+            Opcode::Br16         => 0x6C,
+            Opcode::BrIf8        => 0x6D,
+            Opcode::BrIf16       => 0x6E,
+            Opcode::Call         => 0x6F,
+            Opcode::CallI        => 0x70,
+            Opcode::Ret          => 0x71,
+            Opcode::Push         => 0x72,
+            Opcode::Pop          => 0x73,
+            Opcode::FAdd         => 0x74,
+            Opcode::FSub         => 0x75,
+            Opcode::FMul         => 0x76,
+            Opcode::FDiv         => 0x77,
+            Opcode::FSqrt        => 0x78,
+            Opcode::FMin         => 0x79,
+            Opcode::FMax         => 0x7A,
+            Opcode::FEq          => 0x7B,
+            Opcode::FLt          => 0x7C,
+            Opcode::FGt          => 0x7D,
+            Opcode::FLe          => 0x7E,
+            Opcode::FGe          => 0x7F,
+            Opcode::IToF         => 0x80,
+            Opcode::FToI         => 0x81,
+            Opcode::FPrint       => 0x82,
+            Opcode::VAdd         => 0x83,
+            Opcode::VSub         => 0x84,
+            Opcode::VMul         => 0x85,
+            Opcode::VAnd         => 0x86,
+            Opcode::VOr          => 0x87,
+            Opcode::VXor         => 0x88,
+            Opcode::VFAdd        => 0x89,
+            Opcode::VFSub        => 0x8A,
+            Opcode::VFMul        => 0x8B,
+            Opcode::VFDiv        => 0x8C,
+            Opcode::VLoad        => 0x8D,
+            Opcode::VStore       => 0x8E,
+            Opcode::Cas          => 0x8F,
+            Opcode::Xchg         => 0x90,
+            Opcode::FetchAdd     => 0x91,
+            Opcode::FetchAnd     => 0x92,
+            Opcode::FetchOr      => 0x93,
+            Opcode::SemInit      => 0x94,
+            Opcode::SemUp        => 0x95,
+            Opcode::SemDown      => 0x96,
+            Opcode::Barrier      => 0x97,
+            Opcode::WaitAll      => 0x98,
+            Opcode::WaitAny      => 0x99,
+            Opcode::Send         => 0x9A,
+            Opcode::Recv         => 0x9B,
+            Opcode::TrySend      => 0x9C,
+            Opcode::TryRecv      => 0x9D,
+            Opcode::Tid          => 0x9E,
+            Opcode::TStatus      => 0x9F,
+            Opcode::Join         => 0xA0,
+            Opcode::Spawn        => 0xA1,
         }
     }
 }
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 
 use crate::operand::Operand;
 use crate::parser::Parser;
-use crate::instructions::{ word_opcode, opcode_operands };
+use crate::instructions::{ word_opcodes, opcode_operands, opcode_destinations };
 
 pub struct Assembler {
     code: Box<str>,
@@ -17,16 +17,27 @@ impl Assembler {
     }
 
     pub fn parse(&mut self) -> Box<[u8]> {
-        let labels = self.parse_labels();
-        self.parse_instructions(labels)
+        let mut encodings = Vec::new();
+        let mut labels = HashMap::new();
+        loop {
+            let next = self.parse_labels(&mut encodings, &labels);
+            if next == labels {
+                break;
+            }
+
+            labels = next;
+        }
+
+        self.parse_instructions(&encodings, &labels)
     }
 
-    fn parse_labels(&self) -> HashMap<Box<str>, usize> {
+    fn parse_labels(&self, encodings: &mut Vec<usize>, previous: &HashMap<Box<str>, usize>) -> HashMap<Box<str>, usize> {
         let mut parser = Parser::new(&self.code);
         let mut labels = HashMap::new();
         let mut address = 0;
+        let mut index = 0;
         while let Some(word) = parser.next_word() {
-            let Some(opcode) = word_opcode(word) else {
+            let Some(opcodes) = word_opcodes(word) else {
                 let label = Box::from(word);
                 if labels.contains_key(&label) {
                     parser.error("Label already exists.")
@@ -40,31 +51,50 @@ impl Assembler {
                 continue;
             };
 
+            let words = parser.with_operands(opcode_operands(opcodes[0]).len());
+            if index == encodings.len() {
+                encodings.push(0);
+            }
+
+            let encoding = select_encoding(opcodes, encodings[index], &words, previous, address);
+            encodings[index] = encoding;
+            index += 1;
+
             address += 1;
-            let operands = opcode_operands(opcode);
-            parser.with_operands(operands, &mut address);
+            for operand in opcode_operands(opcodes[encoding]) {
+                address += operand.size();
+            }
         }
 
         labels
     }
 
-    fn parse_instructions(&self, labels: HashMap<Box<str>, usize>) -> Box<[u8]> {
+    fn parse_instructions(&self, encodings: &[usize], labels: &HashMap<Box<str>, usize>) -> Box<[u8]> {
         let mut parser = Parser::new(&self.code);
         let mut program = Vec::new();
+        let mut index = 0;
         while let Some(word) = parser.next_word() {
-            let Some(opcode) = word_opcode(word) else {
+            let Some(opcodes) = word_opcodes(word) else {
                 parser.next_colon();
                 continue;
             };
 
+            let opcode = opcodes[encodings[index]];
+            index += 1;
+
+            let address = program.len();
             program.push(Opcode::to_raw(opcode));
             let operands = opcode_operands(opcode);
-            for operand in operands {
+            for (i, operand) in operands.iter().enumerate() {
                 parser.next_comma();
                 let word = parser.next_word().unwrap();
-                if let Err(error) = operand.parse(word, &mut program, &labels) {
+                if let Err(error) = operand.parse(word, &mut program, labels, address) {
                     parser.error(&error);
                 }
+
+                if opcode_destinations(opcode).contains(&i) && (word == "r0" || word.starts_with("r0..")) {
+                    parser.warning("Writes on register `r0` are discarded.");
+                }
             }
         }
 
@@ -72,28 +102,30 @@ impl Assembler {
     }
 }
 
-impl Parser<'_> {
-    fn with_operands(&mut self, operands: &[Operand], cursor: &mut usize) {
-        let mut iterator = operands.iter().copied();
-        let Some(operand) = iterator.next() else {
-            return;
-        };
+fn select_encoding(opcodes: &[Opcode], minimum: usize, words: &[&str], labels: &HashMap<Box<str>, usize>, address: usize) -> usize {
+    (minimum .. opcodes.len())
+        .find(|&encoding| {
+            let operands: &[Operand] = opcode_operands(opcodes[encoding]);
+            operands.iter().zip(words).all(|(operand, word)| operand.fits(word, labels, address))
+        })
+        .unwrap_or(minimum)
+}
 
-        self.with_operand(operand, cursor);
-        for operand in iterator {
-            if !self.next_comma() {
+impl<'a> Parser<'a> {
+    fn with_operands(&mut self, count: usize) -> Box<[&'a str]> {
+        let mut words = Vec::new();
+        for i in 0 .. count {
+            if i != 0 && !self.next_comma() {
                 self.error("Missing comma.");
             }
 
-            self.with_operand(operand, cursor);
-        }
-    }
+            let Some(word) = self.next_word() else {
+                self.error("Missing operand.");
+            };
 
-    fn with_operand(&mut self, operand: Operand, cursor: &mut usize) {
-        if self.next_word().is_none() {
-            self.error("Missing operand.");
-        };
+            words.push(word);
+        }
 
-        *cursor += operand.size();
+        words.into_boxed_slice()
     }
 }
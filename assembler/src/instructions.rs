@@ -2,47 +2,179 @@ use architecture::Opcode;
 
 use crate::operand::Operand;
 
-pub fn word_opcode(word: &str) -> Option<Opcode> {
+pub fn word_opcodes(word: &str) -> Option<&'static [Opcode]> {
     Some(match word {
-        "nop"     => Opcode::Nop,
-        "move"    => Opcode::Move,
-        "const8"  => Opcode::Const8,
-        "const16" => Opcode::Const16,
-        "const32" => Opcode::Const32,
-        "const64" => Opcode::Const64,
-        "load8"   => Opcode::Load8,
-        "load16"  => Opcode::Load16,
-        "load32"  => Opcode::Load32,
-        "load64"  => Opcode::Load64,
-        "store8"  => Opcode::Store8,
-        "store16" => Opcode::Store16,
-        "store32" => Opcode::Store32,
-        "store64" => Opcode::Store64,
-        "and"     => Opcode::And,
-        "or"      => Opcode::Or,
-        "xor"     => Opcode::Xor,
-        "shl"     => Opcode::ShiftL,
-        "shr"     => Opcode::ShiftR,
-        "add"     => Opcode::Add,
-        "sub"     => Opcode::Sub,
-        "mul"     => Opcode::Mul,
-        "div"     => Opcode::Div,
-        "rem"     => Opcode::Rem,
-        "eq"      => Opcode::Eq,
-        "gt"      => Opcode::Gt,
-        "jump"    => Opcode::Jump,
-        "jumpif"  => Opcode::JumpIf,
-        "wait"    => Opcode::Wait,
-        "lock"    => Opcode::Lock,
-        "unlock"  => Opcode::Unlock,
-        "start"   => Opcode::Start,
-        "stop"    => Opcode::Stop,
-        "halt"    => Opcode::Halt,
-        "scan"    => Opcode::Scan,
-        "print"   => Opcode::Print,
-        "preset"  => Opcode::ProfileReset,
-        "pdump"   => Opcode::ProfileDump,
-        "end"     => Opcode::End,
+        "nop"      => &[Opcode::Nop],
+        "move"     => &[Opcode::Move],
+        "const8"   => &[Opcode::Const8],
+        "const16"  => &[Opcode::Const16],
+        "const32"  => &[Opcode::Const32],
+        "const64"  => &[Opcode::Const64],
+        "load8"    => &[Opcode::Load8, Opcode::Load8O],
+        "load16"   => &[Opcode::Load16, Opcode::Load16O],
+        "load32"   => &[Opcode::Load32, Opcode::Load32O],
+        "load64"   => &[Opcode::Load64, Opcode::Load64O],
+        "store8"   => &[Opcode::Store8, Opcode::Store8O],
+        "store16"  => &[Opcode::Store16, Opcode::Store16O],
+        "store32"  => &[Opcode::Store32, Opcode::Store32O],
+        "store64"  => &[Opcode::Store64, Opcode::Store64O],
+        "and"      => &[Opcode::And],
+        "or"       => &[Opcode::Or],
+        "xor"      => &[Opcode::Xor],
+        "shl"      => &[Opcode::ShiftL],
+        "shr"      => &[Opcode::ShiftR],
+        "add"      => &[Opcode::Add],
+        "sub"      => &[Opcode::Sub],
+        "mul"      => &[Opcode::Mul],
+        "div"      => &[Opcode::Div],
+        "rem"      => &[Opcode::Rem],
+        "eq"       => &[Opcode::Eq],
+        "lt"       => &[Opcode::Lt],
+        "gt"       => &[Opcode::Gt],
+        "jump"     => &[Opcode::Jump, Opcode::Br8, Opcode::Br16, Opcode::JumpI],
+        "jumpif"   => &[Opcode::JumpIf],
+        "wait"     => &[Opcode::Wait],
+        "lock"     => &[Opcode::Lock],
+        "unlock"   => &[Opcode::Unlock],
+        "start"    => &[Opcode::Start],
+        "stop"     => &[Opcode::Stop],
+        "halt"     => &[Opcode::Halt],
+        "scan"     => &[Opcode::Scan],
+        "print"    => &[Opcode::Print],
+        "preset"   => &[Opcode::ProfileReset],
+        "pdump"    => &[Opcode::ProfileDump],
+        "end"      => &[Opcode::End],
+        "sdiv"     => &[Opcode::SDiv],
+        "srem"     => &[Opcode::SRem],
+        "slt"      => &[Opcode::SLt],
+        "sgt"      => &[Opcode::SGt],
+        "sra"      => &[Opcode::ShiftRA],
+        "load8s"   => &[Opcode::Load8S, Opcode::Load8SO],
+        "load16s"  => &[Opcode::Load16S, Opcode::Load16SO],
+        "load32s"  => &[Opcode::Load32S, Opcode::Load32SO],
+        "addc"     => &[Opcode::AddC],
+        "subc"     => &[Opcode::SubC],
+        "mulc"     => &[Opcode::MulC],
+        "mulh"     => &[Opcode::MulH],
+        "not"      => &[Opcode::Not],
+        "andn"     => &[Opcode::AndN],
+        "rotl"     => &[Opcode::RotL],
+        "rotr"     => &[Opcode::RotR],
+        "popcnt"   => &[Opcode::PopCnt],
+        "clz"      => &[Opcode::Clz],
+        "ctz"      => &[Opcode::Ctz],
+        "bswap"    => &[Opcode::BSwap],
+        "bfx"      => &[Opcode::BitExtract],
+        "bfi"      => &[Opcode::BitInsert],
+        "ne"       => &[Opcode::Ne],
+        "le"       => &[Opcode::Le],
+        "ge"       => &[Opcode::Ge],
+        "select"   => &[Opcode::Select],
+        "cmpeq"    => &[Opcode::Eq],
+        "cmpne"    => &[Opcode::Ne],
+        "cmplt"    => &[Opcode::Lt],
+        "cmple"    => &[Opcode::Le],
+        "cmpgt"    => &[Opcode::Gt],
+        "cmpge"    => &[Opcode::Ge],
+        "scmplt"   => &[Opcode::SLt],
+        "scmpgt"   => &[Opcode::SGt],
+        "cmov"     => &[Opcode::Select],
+        "jumpi"    => &[Opcode::JumpI],
+        "jumpifi"  => &[Opcode::JumpIfI],
+        "starti"   => &[Opcode::StartI],
+        "br"       => &[Opcode::Br8, Opcode::Br16],
+        "br8"      => &[Opcode::Br8],
+        "br16"     => &[Opcode::Br16],
+        "brif"     => &[Opcode::BrIf8, Opcode::BrIf16, Opcode::JumpIfI],
+        "brif8"    => &[Opcode::BrIf8],
+        "brif16"   => &[Opcode::BrIf16],
+        "call"     => &[Opcode::Call, Opcode::CallI],
+        "calli"    => &[Opcode::CallI],
+        "ret"      => &[Opcode::Ret],
+        "push"     => &[Opcode::Push],
+        "pop"      => &[Opcode::Pop],
+        "andi"     => &[Opcode::AndI8, Opcode::AndI16, Opcode::AndI32],
+        "andi8"    => &[Opcode::AndI8],
+        "andi16"   => &[Opcode::AndI16],
+        "andi32"   => &[Opcode::AndI32],
+        "ori"      => &[Opcode::OrI8, Opcode::OrI16, Opcode::OrI32],
+        "ori8"     => &[Opcode::OrI8],
+        "ori16"    => &[Opcode::OrI16],
+        "ori32"    => &[Opcode::OrI32],
+        "xori"     => &[Opcode::XorI8, Opcode::XorI16, Opcode::XorI32],
+        "xori8"    => &[Opcode::XorI8],
+        "xori16"   => &[Opcode::XorI16],
+        "xori32"   => &[Opcode::XorI32],
+        "shli"     => &[Opcode::ShiftLI8, Opcode::ShiftLI16, Opcode::ShiftLI32],
+        "shli8"    => &[Opcode::ShiftLI8],
+        "shli16"   => &[Opcode::ShiftLI16],
+        "shli32"   => &[Opcode::ShiftLI32],
+        "shri"     => &[Opcode::ShiftRI8, Opcode::ShiftRI16, Opcode::ShiftRI32],
+        "shri8"    => &[Opcode::ShiftRI8],
+        "shri16"   => &[Opcode::ShiftRI16],
+        "shri32"   => &[Opcode::ShiftRI32],
+        "srai"     => &[Opcode::ShiftRAI8, Opcode::ShiftRAI16, Opcode::ShiftRAI32],
+        "srai8"    => &[Opcode::ShiftRAI8],
+        "srai16"   => &[Opcode::ShiftRAI16],
+        "srai32"   => &[Opcode::ShiftRAI32],
+        "addi"     => &[Opcode::AddI8, Opcode::AddI16, Opcode::AddI32],
+        "addi8"    => &[Opcode::AddI8],
+        "addi16"   => &[Opcode::AddI16],
+        "addi32"   => &[Opcode::AddI32],
+        "subi"     => &[Opcode::SubI8, Opcode::SubI16, Opcode::SubI32],
+        "subi8"    => &[Opcode::SubI8],
+        "subi16"   => &[Opcode::SubI16],
+        "subi32"   => &[Opcode::SubI32],
+        "muli"     => &[Opcode::MulI8, Opcode::MulI16, Opcode::MulI32],
+        "muli8"    => &[Opcode::MulI8],
+        "muli16"   => &[Opcode::MulI16],
+        "muli32"   => &[Opcode::MulI32],
+        "fadd"     => &[Opcode::FAdd],
+        "fsub"     => &[Opcode::FSub],
+        "fmul"     => &[Opcode::FMul],
+        "fdiv"     => &[Opcode::FDiv],
+        "fsqrt"    => &[Opcode::FSqrt],
+        "fmin"     => &[Opcode::FMin],
+        "fmax"     => &[Opcode::FMax],
+        "feq"      => &[Opcode::FEq],
+        "flt"      => &[Opcode::FLt],
+        "fgt"      => &[Opcode::FGt],
+        "fle"      => &[Opcode::FLe],
+        "fge"      => &[Opcode::FGe],
+        "itof"     => &[Opcode::IToF],
+        "ftoi"     => &[Opcode::FToI],
+        "fprint"   => &[Opcode::FPrint],
+        "vadd"     => &[Opcode::VAdd],
+        "vsub"     => &[Opcode::VSub],
+        "vmul"     => &[Opcode::VMul],
+        "vand"     => &[Opcode::VAnd],
+        "vor"      => &[Opcode::VOr],
+        "vxor"     => &[Opcode::VXor],
+        "vfadd"    => &[Opcode::VFAdd],
+        "vfsub"    => &[Opcode::VFSub],
+        "vfmul"    => &[Opcode::VFMul],
+        "vfdiv"    => &[Opcode::VFDiv],
+        "vload"    => &[Opcode::VLoad],
+        "vstore"   => &[Opcode::VStore],
+        "cas"      => &[Opcode::Cas],
+        "xchg"     => &[Opcode::Xchg],
+        "fetchadd" => &[Opcode::FetchAdd],
+        "fetchand" => &[Opcode::FetchAnd],
+        "fetchor"  => &[Opcode::FetchOr],
+        "sinit"    => &[Opcode::SemInit],
+        "sup"      => &[Opcode::SemUp],
+        "sdown"    => &[Opcode::SemDown],
+        "barrier"  => &[Opcode::Barrier],
+        "waitall"  => &[Opcode::WaitAll],
+        "waitany"  => &[Opcode::WaitAny],
+        "send"     => &[Opcode::Send],
+        "recv"     => &[Opcode::Recv],
+        "trysend"  => &[Opcode::TrySend],
+        "tryrecv"  => &[Opcode::TryRecv],
+        "tid"      => &[Opcode::Tid],
+        "tstatus"  => &[Opcode::TStatus],
+        "join"     => &[Opcode::Join],
+        "spawn"    => &[Opcode::Spawn],
         _ => return None,
     })
 }
@@ -89,5 +221,166 @@ pub fn opcode_operands(opcode: Opcode) -> &'static [Operand] {
         Opcode::ProfileReset => &[],
         Opcode::ProfileDump  => &[],
         Opcode::End          => &[],
+        Opcode::SDiv         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::SRem         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::SLt          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::SGt          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::ShiftRA      => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Load8S       => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Load16S      => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Load32S      => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::AddC         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::SubC         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::MulC         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::MulH         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Not          => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::AndN         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::RotL         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::RotR         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::PopCnt       => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Clz          => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Ctz          => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::BSwap        => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::BitExtract   => &[Operand::Register, Operand::Const8, Operand::Const8, Operand::Register, Operand::Lock],
+        Opcode::BitInsert    => &[Operand::Register, Operand::Register, Operand::Const8, Operand::Const8, Operand::Register, Operand::Lock],
+        Opcode::Ne           => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Le           => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Ge           => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Select       => &[Operand::Register, Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::AndI8        => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::AndI16       => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::AndI32       => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::OrI8         => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::OrI16        => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::OrI32        => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::XorI8        => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::XorI16       => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::XorI32       => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::ShiftLI8     => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::ShiftLI16    => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::ShiftLI32    => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::ShiftRI8     => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::ShiftRI16    => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::ShiftRI32    => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::ShiftRAI8    => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::ShiftRAI16   => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::ShiftRAI32   => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::AddI8        => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::AddI16       => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::AddI32       => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::SubI8        => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::SubI16       => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::SubI32       => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::MulI8        => &[Operand::Register, Operand::Immediate8, Operand::Register, Operand::Lock],
+        Opcode::MulI16       => &[Operand::Register, Operand::Immediate16, Operand::Register, Operand::Lock],
+        Opcode::MulI32       => &[Operand::Register, Operand::Immediate32, Operand::Register, Operand::Lock],
+        Opcode::Load8O       => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Load16O      => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Load32O      => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Load64O      => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Load8SO      => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Load16SO     => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Load32SO     => &[Operand::Offset, Operand::Register, Operand::Lock],
+        Opcode::Store8O      => &[Operand::Register, Operand::Offset, Operand::Lock],
+        Opcode::Store16O     => &[Operand::Register, Operand::Offset, Operand::Lock],
+        Opcode::Store32O     => &[Operand::Register, Operand::Offset, Operand::Lock],
+        Opcode::Store64O     => &[Operand::Register, Operand::Offset, Operand::Lock],
+        Opcode::JumpI        => &[Operand::Const32],
+        Opcode::JumpIfI      => &[Operand::Register, Operand::Const32],
+        Opcode::StartI       => &[Operand::Thread, Operand::Const32],
+        Opcode::Br8          => &[Operand::Relative8],
+        Opcode::Br16         => &[Operand::Relative16],
+        Opcode::BrIf8        => &[Operand::Register, Operand::Relative8],
+        Opcode::BrIf16       => &[Operand::Register, Operand::Relative16],
+        Opcode::Call         => &[Operand::Register, Operand::Lock],
+        Opcode::CallI        => &[Operand::Const32, Operand::Lock],
+        Opcode::Ret          => &[Operand::Lock],
+        Opcode::Push         => &[Operand::Register, Operand::Lock],
+        Opcode::Pop          => &[Operand::Register, Operand::Lock],
+        Opcode::FAdd         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FSub         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FMul         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FDiv         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FSqrt        => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FMin         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FMax         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FEq          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FLt          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FGt          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FLe          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FGe          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::IToF         => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FToI         => &[Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FPrint       => &[Operand::Register],
+        Opcode::VAdd         => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VSub         => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VMul         => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VAnd         => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VOr          => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VXor         => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VFAdd        => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VFSub        => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VFMul        => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VFDiv        => &[Operand::Registers, Operand::Registers, Operand::Registers, Operand::Lock],
+        Opcode::VLoad        => &[Operand::Register, Operand::Registers, Operand::Lock],
+        Opcode::VStore       => &[Operand::Registers, Operand::Register, Operand::Lock],
+        Opcode::Cas          => &[Operand::Register, Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::Xchg         => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FetchAdd     => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FetchAnd     => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::FetchOr      => &[Operand::Register, Operand::Register, Operand::Register, Operand::Lock],
+        Opcode::SemInit      => &[Operand::Semaphore, Operand::Register],
+        Opcode::SemUp        => &[Operand::Semaphore],
+        Opcode::SemDown      => &[Operand::Semaphore],
+        Opcode::Barrier      => &[Operand::Barrier, Operand::Const8],
+        Opcode::WaitAll      => &[Operand::Register],
+        Opcode::WaitAny      => &[Operand::Register, Operand::Register],
+        Opcode::Send         => &[Operand::Channel, Operand::Register],
+        Opcode::Recv         => &[Operand::Channel, Operand::Register],
+        Opcode::TrySend      => &[Operand::Channel, Operand::Register, Operand::Register],
+        Opcode::TryRecv      => &[Operand::Channel, Operand::Register, Operand::Register],
+        Opcode::Tid          => &[Operand::Register],
+        Opcode::TStatus      => &[Operand::Thread, Operand::Register],
+        Opcode::Join         => &[Operand::Thread],
+        Opcode::Spawn        => &[Operand::Register, Operand::Register],
+    }
+}
+
+pub fn opcode_destinations(opcode: Opcode) -> &'static [usize] {
+    match opcode {
+        Opcode::Const8 | Opcode::Const16 | Opcode::Const32 | Opcode::Const64
+        | Opcode::Scan | Opcode::Pop | Opcode::Tid => &[0],
+        Opcode::Move
+        | Opcode::Load8 | Opcode::Load16 | Opcode::Load32 | Opcode::Load64
+        | Opcode::Load8S | Opcode::Load16S | Opcode::Load32S
+        | Opcode::Load8O | Opcode::Load16O | Opcode::Load32O | Opcode::Load64O
+        | Opcode::Load8SO | Opcode::Load16SO | Opcode::Load32SO
+        | Opcode::Not | Opcode::PopCnt | Opcode::Clz | Opcode::Ctz | Opcode::BSwap
+        | Opcode::FSqrt | Opcode::IToF | Opcode::FToI
+        | Opcode::VLoad | Opcode::WaitAny | Opcode::Recv | Opcode::TStatus | Opcode::Spawn => &[1],
+        Opcode::And | Opcode::Or | Opcode::Xor | Opcode::ShiftL | Opcode::ShiftR | Opcode::ShiftRA
+        | Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div | Opcode::Rem
+        | Opcode::Eq | Opcode::Lt | Opcode::Gt | Opcode::Ne | Opcode::Le | Opcode::Ge
+        | Opcode::SDiv | Opcode::SRem | Opcode::SLt | Opcode::SGt
+        | Opcode::AddC | Opcode::SubC | Opcode::MulC | Opcode::MulH
+        | Opcode::AndN | Opcode::RotL | Opcode::RotR
+        | Opcode::AndI8 | Opcode::AndI16 | Opcode::AndI32
+        | Opcode::OrI8 | Opcode::OrI16 | Opcode::OrI32
+        | Opcode::XorI8 | Opcode::XorI16 | Opcode::XorI32
+        | Opcode::ShiftLI8 | Opcode::ShiftLI16 | Opcode::ShiftLI32
+        | Opcode::ShiftRI8 | Opcode::ShiftRI16 | Opcode::ShiftRI32
+        | Opcode::ShiftRAI8 | Opcode::ShiftRAI16 | Opcode::ShiftRAI32
+        | Opcode::AddI8 | Opcode::AddI16 | Opcode::AddI32
+        | Opcode::SubI8 | Opcode::SubI16 | Opcode::SubI32
+        | Opcode::MulI8 | Opcode::MulI16 | Opcode::MulI32
+        | Opcode::FAdd | Opcode::FSub | Opcode::FMul | Opcode::FDiv | Opcode::FMin | Opcode::FMax
+        | Opcode::FEq | Opcode::FLt | Opcode::FGt | Opcode::FLe | Opcode::FGe
+        | Opcode::VAdd | Opcode::VSub | Opcode::VMul | Opcode::VAnd | Opcode::VOr | Opcode::VXor
+        | Opcode::VFAdd | Opcode::VFSub | Opcode::VFMul | Opcode::VFDiv
+        | Opcode::Xchg | Opcode::FetchAdd | Opcode::FetchAnd | Opcode::FetchOr | Opcode::TrySend => &[2],
+        Opcode::BitExtract | Opcode::Select | Opcode::Cas => &[3],
+        Opcode::BitInsert => &[4],
+        Opcode::TryRecv => &[1, 2],
+        _ => &[],
     }
 }
@@ -1,4 +1,4 @@
-use architecture::{ REGISTERS_COUNT, LOCKS_COUNT, THREADS_COUNT };
+use architecture::{ REGISTERS_COUNT, LOCKS_COUNT, THREADS_COUNT, SEMAPHORES_COUNT, BARRIERS_COUNT, CHANNELS_COUNT };
 use std::collections::HashMap;
 
 use crate::parser::ParserResult;
@@ -9,33 +9,74 @@ pub enum Operand {
     Const16,
     Const32,
     Const64,
+    Immediate8,
+    Immediate16,
+    Immediate32,
+    Relative8,
+    Relative16,
     Register,
+    Registers,
+    Offset,
     Lock,
     Thread,
+    Semaphore,
+    Barrier,
+    Channel,
 }
 
 impl Operand {
     pub fn size(self) -> usize {
         match self {
-            Operand::Const8   => 1,
-            Operand::Const16  => 2,
-            Operand::Const32  => 4,
-            Operand::Const64  => 8,
-            Operand::Register => 1,
-            Operand::Lock     => 1,
-            Operand::Thread   => 1,
+            Operand::Const8      => 1,
+            Operand::Const16     => 2,
+            Operand::Const32     => 4,
+            Operand::Const64     => 8,
+            Operand::Immediate8  => 1,
+            Operand::Immediate16 => 2,
+            Operand::Immediate32 => 4,
+            Operand::Relative8   => 1,
+            Operand::Relative16  => 2,
+            Operand::Register    => 1,
+            Operand::Registers   => 2,
+            Operand::Offset      => 3,
+            Operand::Lock        => 1,
+            Operand::Thread      => 1,
+            Operand::Semaphore   => 1,
+            Operand::Barrier     => 1,
+            Operand::Channel     => 1,
         }
     }
 
-    pub fn parse(self, word: &str, program: &mut Vec<u8>, labels: &HashMap<Box<str>, usize>) -> ParserResult<()> {
+    pub fn fits(self, word: &str, labels: &HashMap<Box<str>, usize>, address: usize) -> bool {
+        let unresolved = match self {
+            Operand::Const8 | Operand::Const16 | Operand::Const32 | Operand::Const64
+            | Operand::Immediate8 | Operand::Immediate16 | Operand::Immediate32
+            | Operand::Relative8 | Operand::Relative16 => !check_signed_integer(word) && !labels.contains_key(word),
+            _ => false,
+        };
+
+        unresolved || self.parse(word, &mut Vec::new(), labels, address).is_ok()
+    }
+
+    pub fn parse(self, word: &str, program: &mut Vec<u8>, labels: &HashMap<Box<str>, usize>, address: usize) -> ParserResult<()> {
         match self {
-            Operand::Const8   => program.extend_from_slice(&parse_const8(word, labels)?),
-            Operand::Const16  => program.extend_from_slice(&parse_const16(word, labels)?),
-            Operand::Const32  => program.extend_from_slice(&parse_const32(word, labels)?),
-            Operand::Const64  => program.extend_from_slice(&parse_const64(word, labels)?),
-            Operand::Register => program.push(parse_register(word)?),
-            Operand::Lock     => program.push(parse_lock(word)?),
-            Operand::Thread   => program.push(parse_thread(word)?),
+            Operand::Const8      => program.extend_from_slice(&parse_const8(word, labels)?),
+            Operand::Const16     => program.extend_from_slice(&parse_const16(word, labels)?),
+            Operand::Const32     => program.extend_from_slice(&parse_const32(word, labels)?),
+            Operand::Const64     => program.extend_from_slice(&parse_const64(word, labels)?),
+            Operand::Immediate8  => program.extend_from_slice(&parse_immediate8(word, labels)?),
+            Operand::Immediate16 => program.extend_from_slice(&parse_immediate16(word, labels)?),
+            Operand::Immediate32 => program.extend_from_slice(&parse_immediate32(word, labels)?),
+            Operand::Relative8   => program.extend_from_slice(&parse_relative8(word, labels, address)?),
+            Operand::Relative16  => program.extend_from_slice(&parse_relative16(word, labels, address)?),
+            Operand::Register    => program.push(parse_register(word)?),
+            Operand::Registers   => program.extend_from_slice(&parse_register_range(word)?),
+            Operand::Offset      => program.extend_from_slice(&parse_offset(word)?),
+            Operand::Lock        => program.push(parse_lock(word)?),
+            Operand::Thread      => program.push(parse_thread(word)?),
+            Operand::Semaphore   => program.push(parse_semaphore(word)?),
+            Operand::Barrier     => program.push(parse_barrier(word)?),
+            Operand::Channel     => program.push(parse_channel(word)?),
         }
 
         Ok(())
@@ -46,6 +87,15 @@ fn check_integer(word: &str) -> bool {
     word.chars().all(|character| character.is_numeric())
 }
 
+fn check_signed_integer(word: &str) -> bool {
+    let digits = word.strip_prefix(['+', '-']).unwrap_or(word);
+    !digits.is_empty() && check_integer(digits)
+}
+
+fn check_float(word: &str) -> bool {
+    word.contains('.') && word.parse::<f64>().is_ok()
+}
+
 fn parse_label(word: &str, labels: &HashMap<Box<str>, usize>) -> ParserResult<usize> {
     let Some(address) = labels.get(word).copied() else {
         return Err(Box::from("Label not found."));
@@ -85,9 +135,91 @@ fn parse_const32(word: &str, labels: &HashMap<Box<str>, usize>) -> ParserResult<
 }
 
 fn parse_const64(word: &str, labels: &HashMap<Box<str>, usize>) -> ParserResult<[u8; 8]> {
+    if check_float(word) {
+        let Ok(float) = word.parse::<f64>() else {
+            return Err(Box::from("Invalid constant float."));
+        };
+
+        return Ok(float.to_bits().to_be_bytes());
+    }
+
     parse_const!(u64, word, labels)
 }
 
+macro parse_immediate($type:ty, $word:expr, $labels:expr) {{
+    let immediate = if check_signed_integer($word) {
+        let Ok(integer) = $word.parse::<$type>() else {
+            return Err(Box::from("Invalid immediate integer."));
+        };
+
+        integer
+    } else {
+        let Ok(address) = <$type>::try_from(parse_label($word, $labels)?) else {
+            return Err(Box::from("Invalid immediate address."));
+        };
+
+        address
+    };
+
+    Ok(immediate.to_be_bytes())
+}}
+
+fn parse_immediate8(word: &str, labels: &HashMap<Box<str>, usize>) -> ParserResult<[u8; 1]> {
+    parse_immediate!(i8, word, labels)
+}
+
+fn parse_immediate16(word: &str, labels: &HashMap<Box<str>, usize>) -> ParserResult<[u8; 2]> {
+    parse_immediate!(i16, word, labels)
+}
+
+fn parse_immediate32(word: &str, labels: &HashMap<Box<str>, usize>) -> ParserResult<[u8; 4]> {
+    parse_immediate!(i32, word, labels)
+}
+
+macro parse_relative($type:ty, $word:expr, $labels:expr, $address:expr) {{
+    let offset = if check_signed_integer($word) {
+        let Ok(integer) = $word.parse::<$type>() else {
+            return Err(Box::from("Invalid relative integer."));
+        };
+
+        integer
+    } else {
+        let target = parse_label($word, $labels)?;
+        let Ok(offset) = <$type>::try_from(target as i64 - $address as i64) else {
+            return Err(Box::from("Invalid relative address."));
+        };
+
+        offset
+    };
+
+    Ok(offset.to_be_bytes())
+}}
+
+fn parse_relative8(word: &str, labels: &HashMap<Box<str>, usize>, address: usize) -> ParserResult<[u8; 1]> {
+    parse_relative!(i8, word, labels, address)
+}
+
+fn parse_relative16(word: &str, labels: &HashMap<Box<str>, usize>, address: usize) -> ParserResult<[u8; 2]> {
+    parse_relative!(i16, word, labels, address)
+}
+
+fn parse_offset(word: &str) -> ParserResult<[u8; 3]> {
+    let Some((register, offset)) = word.strip_suffix(']').and_then(|word| word.split_once('[')) else {
+        return Err(Box::from("Wrong offset syntax."));
+    };
+
+    if !check_signed_integer(offset) {
+        return Err(Box::from("Wrong offset integer."));
+    }
+
+    let Ok(offset) = offset.parse::<i16>() else {
+        return Err(Box::from("Invalid offset integer."));
+    };
+
+    let [high, low] = offset.to_be_bytes();
+    Ok([parse_register(register)?, high, low])
+}
+
 fn parse_register(word: &str) -> ParserResult<u8> {
     let (prefix, index) = word.split_at(1);
     if prefix != "r" {
@@ -105,6 +237,17 @@ fn parse_register(word: &str) -> ParserResult<u8> {
     Ok(register.to_be())
 }
 
+fn parse_register_range(word: &str) -> ParserResult<[u8; 2]> {
+    let (first, last) = word.split_once("..").unwrap_or((word, word));
+    let first = parse_register(first)?;
+    let last  = parse_register(last)?;
+    if first > last {
+        return Err(Box::from("Invalid register range."));
+    }
+
+    Ok([first, last])
+}
+
 fn parse_lock(word: &str) -> ParserResult<u8> {
     let (prefix, index) = word.split_at(1);
     if prefix != "l" {
@@ -138,3 +281,51 @@ fn parse_thread(word: &str) -> ParserResult<u8> {
 
     Ok(thread.to_be())
 }
+
+fn parse_semaphore(word: &str) -> ParserResult<u8> {
+    let Some(index) = word.strip_prefix('s') else {
+        return Err(Box::from("Wrong semaphore prefix."));
+    };
+
+    let Ok(semaphore) = index.parse::<u8>() else {
+        return Err(Box::from("Wrong semaphore index."));
+    };
+
+    if semaphore as usize >= SEMAPHORES_COUNT {
+        return Err(Box::from("Invalid semaphore index."));
+    }
+
+    Ok(semaphore)
+}
+
+fn parse_barrier(word: &str) -> ParserResult<u8> {
+    let Some(index) = word.strip_prefix('b') else {
+        return Err(Box::from("Wrong barrier prefix."));
+    };
+
+    let Ok(barrier) = index.parse::<u8>() else {
+        return Err(Box::from("Wrong barrier index."));
+    };
+
+    if barrier as usize >= BARRIERS_COUNT {
+        return Err(Box::from("Invalid barrier index."));
+    }
+
+    Ok(barrier)
+}
+
+fn parse_channel(word: &str) -> ParserResult<u8> {
+    let Some(index) = word.strip_prefix("ch") else {
+        return Err(Box::from("Wrong channel prefix."));
+    };
+
+    let Ok(channel) = index.parse::<u8>() else {
+        return Err(Box::from("Wrong channel index."));
+    };
+
+    if channel as usize >= CHANNELS_COUNT {
+        return Err(Box::from("Invalid channel index."));
+    }
+
+    Ok(channel)
+}
@@ -27,11 +27,15 @@ impl<'a> Parser<'a> {
         self.colon()
     }
 
-    pub fn next_word(&mut self) -> Option<&str> {
+    pub fn next_word(&mut self) -> Option<&'a str> {
         self.next();
         self.word()
     }
 
+    pub fn warning(&self, message: &str) {
+        println!("WARNING POSITION {}: {}", self.previous, message);
+    }
+
     pub fn error(&self, message: &str) -> ! {
         println!("ERROR POSITION {}: {}", self.previous, message);
         exit(0);
@@ -85,9 +89,9 @@ impl<'a> Parser<'a> {
         true
     }
 
-    fn word(&mut self) -> Option<&str> {
+    fn word(&mut self) -> Option<&'a str> {
         while let Some(character) = self.lookahead() {
-            if !character.is_alphanumeric() {
+            if !character.is_alphanumeric() && !matches!(character, '_' | '+' | '-' | '.' | '[' | ']') {
                 break;
             }
 
@@ -1,76 +1,180 @@
+mod barrier;
+mod callback;
+mod channel;
+mod checker;
+mod deadlock;
 mod error;
 mod instructions;
 mod lock;
 mod memory;
+mod operation;
+mod race;
 mod register;
+mod schedule;
+mod semaphore;
+mod snapshot;
+mod superblock;
 mod thread;
 
+use std::fmt::Arguments;
+use std::fs::read_to_string;
 use std::io::stdin;
-use std::rc::Rc;
+use std::path::{ Path, PathBuf };
 
 use architecture::Opcode;
 
+use barrier::Barriers;
+use callback::{ Callback, Operation };
+use channel::Channels;
+use checker::Capture;
 use lock::Locks;
 use memory::Memory;
+use operation::{ operation_immediate, operation_offset, operation_vector };
+use race::Races;
 use register::Registers;
-use thread::{ Threads, ThreadId };
+use schedule::Schedule;
+use semaphore::Semaphores;
+use superblock::Superblocks;
+use thread::{ Origin, Threads, ThreadId, ThreadStatus };
 
+use crate::options::{ Hazards, Options };
 use crate::program::Program;
-use crate::time::*;
+use crate::random::Random;
 
+#[derive(Clone)]
 pub struct Machine<'a> {
     program: &'a Program,
     threads: Threads,
     registers: Registers,
     locks: Locks,
+    semaphores: Semaphores,
+    barriers: Barriers,
+    channels: Channels,
     memory: Memory,
-    callbacks: Vec<(usize, Rc<dyn Fn(&mut Machine)>)>,
+    callbacks: Vec<Callback>,
     counter: usize,
+    elapsed: usize,
+    superblocks: Option<Superblocks>,
+    races: Option<Races>,
+    current: Option<Origin>,
+    hazards: Option<Hazards>,
+    random: Option<Random>,
+    jitter: usize,
+    schedule: Option<Schedule>,
+    capture: Option<Capture>,
+    save: Option<(usize, PathBuf)>,
+    strict: bool,
 }
 
 impl<'a> Machine<'a> {
-    pub fn new(program: &'a Program) -> Self {
-        Self {
+    pub fn new(program: &'a Program, options: &Options) -> Self {
+        let mut machine = Self {
             program,
             threads: Threads::new(),
             registers: Registers::new(),
             locks: Locks::new(),
+            semaphores: Semaphores::new(),
+            barriers: Barriers::new(),
+            channels: Channels::new(),
             memory: Memory::new(),
             callbacks: Vec::new(),
             counter: 0,
+            elapsed: 0,
+            superblocks: options.superblocks.then(Superblocks::new),
+            races: options.races.then(Races::new),
+            current: None,
+            hazards: options.hazards,
+            random: options.seed.map(Random::new),
+            jitter: options.jitter,
+            schedule: options.schedule.as_deref().map(|path| Schedule::parse(&read_to_string(path).unwrap(), program.fingerprint())),
+            capture: None,
+            save: options.save_at.map(|cycle| (cycle, options.save_to.as_deref().map_or_else(|| Path::new(&*options.input).with_extension("snapshot"), PathBuf::from))),
+            strict: options.strict,
+        };
+
+        match options.restore.as_deref() {
+            Some(path) => machine.snapshot_restore(Path::new(path)),
+            None       => machine.thread_start(ThreadId::from_raw(0).unwrap(), 0),
         }
+
+        machine
     }
 
     pub fn run(&mut self) {
-        self.threads.get_mut(ThreadId::from_raw(0).unwrap()).start();
         loop {
-            let actives = self.threads.get_actives();
-            if actives.is_empty() && self.callbacks.is_empty() {
-                self.error_pause();
+            if let Some((_, path)) = self.save.as_ref().filter(|(cycle, _)| *cycle == self.elapsed) {
+                self.snapshot_save(path);
             }
 
-            for thread in self.threads.iter_mut() {
-                thread.profile_update();
+            let mut actives = self.cycle_start();
+            if let Some(random) = self.random.as_mut() {
+                random.shuffle(&mut actives);
             }
 
-            for thread in self.threads.get_actives().into_iter().copied() {
-                let opcode = self.next_opcode(thread);
-                self.run_instruction(thread, opcode);
+            if let Some(schedule) = self.schedule.as_ref() {
+                if !schedule.order(self.elapsed, &mut actives) {
+                    self.error_schedule_threads(&actives);
+                }
+            }
+
+            self.cycle_threads(&actives);
+
+            let mut callbacks = self.cycle_callbacks();
+            if let Some(random) = self.random.as_mut() {
+                random.shuffle(&mut callbacks);
             }
 
-            for callback in self.callbacks.clone() {
-                if callback.0 != self.counter {
-                    continue;
+            if let Some(schedule) = self.schedule.as_ref() {
+                if !schedule.permute(self.elapsed, &mut callbacks) {
+                    self.error_schedule_callbacks(callbacks.len());
                 }
+            }
 
-                callback.1(self);
+            for callback in callbacks.iter() {
+                self.callback_run(callback);
             }
 
-            self.callbacks.retain(|callback| callback.0 != self.counter);
+            self.cycle_end();
+        }
+    }
+
+    fn cycle_start(&mut self) -> Box<[ThreadId]> {
+        self.deadlock_check();
 
-            self.registers.reset();
-            self.counter += 1;
+        for thread in self.threads.iter_mut() {
+            thread.profile_update();
         }
+
+        self.threads.get_actives()
+    }
+
+    fn cycle_threads(&mut self, actives: &[ThreadId]) {
+        for thread in actives.iter().copied() {
+            self.threads.get_mut(thread).fetch();
+            self.current = Some(self.origin(thread));
+            if self.run_superblock(thread) {
+                continue;
+            }
+
+            let opcode = self.next_opcode(thread);
+            self.run_instruction(thread, opcode);
+        }
+    }
+
+    fn cycle_callbacks(&self) -> Box<[Callback]> {
+        self.callbacks.iter()
+            .filter(|callback| callback.cycle() == self.counter)
+            .cloned()
+            .collect()
+    }
+
+    fn cycle_end(&mut self) {
+        self.callbacks.retain(|callback| callback.cycle() != self.counter);
+
+        self.registers.reset();
+        self.memory.reset();
+        self.counter += 1;
+        self.elapsed += 1;
     }
 
     pub fn run_instruction(&mut self, thread_id: ThreadId, opcode: Opcode) {
@@ -95,80 +199,71 @@ impl<'a> Machine<'a> {
             Opcode::Const64 => {
                 self.instruction_const(thread_id, |machine, thread_id| machine.next_const64(thread_id));
             },
-            Opcode::Load8 => {
-                self.instruction_load(thread_id, |machine, thread_id, address| machine.load8(thread_id, address) as u64);
-            },
-            Opcode::Load16 => {
-                self.instruction_load(thread_id, |machine, thread_id, address| machine.load16(thread_id, address) as u64);
-            },
-            Opcode::Load32 => {
-                self.instruction_load(thread_id, |machine, thread_id, address| machine.load32(thread_id, address) as u64);
-            },
-            Opcode::Load64 => {
-                self.instruction_load(thread_id, |machine, thread_id, address| machine.load64(thread_id, address) as u64);
+            Opcode::Load8 | Opcode::Load16 | Opcode::Load32 | Opcode::Load64
+            | Opcode::Load8S | Opcode::Load16S | Opcode::Load32S => {
+                self.instruction_load(thread_id, opcode);
             },
-            Opcode::Store8 => {
-                self.instruction_store(thread_id, |machine, thread_id, address, value| machine.store8(thread_id, address, value as u8));
+            Opcode::Store8 | Opcode::Store16 | Opcode::Store32 | Opcode::Store64 => {
+                self.instruction_store(thread_id, opcode);
             },
-            Opcode::Store16 => {
-                self.instruction_store(thread_id, |machine, thread_id, address, value| machine.store16(thread_id, address, value as u16));
+            Opcode::Load8O | Opcode::Load16O | Opcode::Load32O | Opcode::Load64O
+            | Opcode::Load8SO | Opcode::Load16SO | Opcode::Load32SO => {
+                self.instruction_load_offset(thread_id, operation_offset(opcode).unwrap());
             },
-            Opcode::Store32 => {
-                self.instruction_store(thread_id, |machine, thread_id, address, value| machine.store32(thread_id, address, value as u32));
+            Opcode::Store8O | Opcode::Store16O | Opcode::Store32O | Opcode::Store64O => {
+                self.instruction_store_offset(thread_id, operation_offset(opcode).unwrap());
             },
-            Opcode::Store64 => {
-                self.instruction_store(thread_id, |machine, thread_id, address, value| machine.store64(thread_id, address, value as u64));
+            Opcode::And | Opcode::Or | Opcode::Xor | Opcode::ShiftL | Opcode::ShiftR
+            | Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div | Opcode::Rem
+            | Opcode::Eq | Opcode::Lt | Opcode::Gt
+            | Opcode::SDiv | Opcode::SRem | Opcode::SLt | Opcode::SGt | Opcode::ShiftRA
+            | Opcode::AddC | Opcode::SubC | Opcode::MulC | Opcode::MulH
+            | Opcode::AndN | Opcode::RotL | Opcode::RotR
+            | Opcode::Ne | Opcode::Le | Opcode::Ge
+            | Opcode::FAdd | Opcode::FSub | Opcode::FMul | Opcode::FDiv | Opcode::FMin | Opcode::FMax
+            | Opcode::FEq | Opcode::FLt | Opcode::FGt | Opcode::FLe | Opcode::FGe => {
+                self.instruction_calcul(thread_id, opcode);
             },
-            Opcode::And => {
-                self.instruction_calcul(thread_id, TIME_AND, |_, _, a, b| a & b);
+            Opcode::AndI8 | Opcode::AndI16 | Opcode::AndI32
+            | Opcode::OrI8 | Opcode::OrI16 | Opcode::OrI32
+            | Opcode::XorI8 | Opcode::XorI16 | Opcode::XorI32
+            | Opcode::ShiftLI8 | Opcode::ShiftLI16 | Opcode::ShiftLI32
+            | Opcode::ShiftRI8 | Opcode::ShiftRI16 | Opcode::ShiftRI32
+            | Opcode::ShiftRAI8 | Opcode::ShiftRAI16 | Opcode::ShiftRAI32
+            | Opcode::AddI8 | Opcode::AddI16 | Opcode::AddI32
+            | Opcode::SubI8 | Opcode::SubI16 | Opcode::SubI32
+            | Opcode::MulI8 | Opcode::MulI16 | Opcode::MulI32 => {
+                let (opcode, size) = operation_immediate(opcode).unwrap();
+                self.instruction_calcul_immediate(thread_id, opcode, size);
             },
-            Opcode::Or => {
-                self.instruction_calcul(thread_id, TIME_OR,  |_, _, a, b| a | b);
+            Opcode::Not | Opcode::PopCnt | Opcode::Clz | Opcode::Ctz | Opcode::BSwap
+            | Opcode::FSqrt | Opcode::IToF | Opcode::FToI => {
+                self.instruction_unary(thread_id, opcode);
             },
-            Opcode::Xor => {
-                self.instruction_calcul(thread_id, TIME_XOR, |_, _, a, b| a ^ b);
+            Opcode::VAdd | Opcode::VSub | Opcode::VMul | Opcode::VAnd | Opcode::VOr | Opcode::VXor
+            | Opcode::VFAdd | Opcode::VFSub | Opcode::VFMul | Opcode::VFDiv => {
+                self.instruction_vector_calcul(thread_id, operation_vector(opcode).unwrap());
             },
-            Opcode::ShiftL => {
-                self.instruction_calcul(thread_id, TIME_SHL, |_, _, a, b| a << b);
+            Opcode::VLoad => {
+                self.instruction_vector_load(thread_id);
             },
-            Opcode::ShiftR => {
-                self.instruction_calcul(thread_id, TIME_SHR, |_, _, a, b| a >> b);
+            Opcode::VStore => {
+                self.instruction_vector_store(thread_id);
             },
-            Opcode::Add => {
-                self.instruction_calcul(thread_id, TIME_ADD, |_, _, a, b| a + b);
+            Opcode::Xchg | Opcode::FetchAdd | Opcode::FetchAnd | Opcode::FetchOr => {
+                self.instruction_atomic(thread_id, opcode);
             },
-            Opcode::Sub => {
-                self.instruction_calcul(thread_id, TIME_SUB, |_, _, a, b| a - b);
+            Opcode::Cas => {
+                self.instruction_cas(thread_id);
             },
-            Opcode::Mul => {
-                self.instruction_calcul(thread_id, TIME_MUL, |_, _, a, b| a * b);
-            },
-            Opcode::Div => {
-                self.instruction_calcul(thread_id, TIME_DIV, |machine, thread_id, a, b| {
-                    if b == 0 {
-                        machine.error_division_by_zero(thread_id);
-                    }
-
-                    a / b
-                });
-            },
-            Opcode::Rem => {
-                self.instruction_calcul(thread_id, TIME_REM, |machine, thread_id, a, b| {
-                    if b == 0 {
-                        machine.error_division_by_zero(thread_id);
-                    }
-
-                    a % b
-                });
+            Opcode::BitExtract => {
+                self.instruction_bit_extract(thread_id);
             },
-            Opcode::Eq => {
-                self.instruction_calcul(thread_id, TIME_EQ, |_, _, a, b| if a == b { 0 } else { 1 });
+            Opcode::BitInsert => {
+                self.instruction_bit_insert(thread_id);
             },
-            Opcode::Lt => {
-                self.instruction_calcul(thread_id, TIME_LT, |_, _, a, b| if a < b { 0 } else { 1 });
-            },
-            Opcode::Gt => {
-                self.instruction_calcul(thread_id, TIME_GT, |_, _, a, b| if a > b { 0 } else { 1 });
+            Opcode::Select => {
+                self.instruction_select(thread_id);
             },
             Opcode::Jump => {
                 let address = self.next_register(thread_id);
@@ -190,27 +285,181 @@ impl<'a> Machine<'a> {
                     thread.jump(address);
                 }
             },
+            Opcode::JumpI => {
+                let address = self.next_const32(thread_id);
+
+                let thread = self.threads.get_mut(thread_id);
+                thread.jump(address);
+            },
+            Opcode::JumpIfI => {
+                let condition = self.next_register(thread_id);
+                let address   = self.next_const32(thread_id);
+
+                let condition = self.register_read(condition);
+
+                if condition == 0 {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.jump(address);
+                }
+            },
+            Opcode::Br8 | Opcode::Br16 => {
+                let offset = self.next_immediate(thread_id, if let Opcode::Br8 = opcode { 1 } else { 2 });
+
+                let address = self.origin(thread_id).address().wrapping_add(offset);
+
+                let thread = self.threads.get_mut(thread_id);
+                thread.jump(address);
+            },
+            Opcode::BrIf8 | Opcode::BrIf16 => {
+                let condition = self.next_register(thread_id);
+                let offset    = self.next_immediate(thread_id, if let Opcode::BrIf8 = opcode { 1 } else { 2 });
+
+                let address   = self.origin(thread_id).address().wrapping_add(offset);
+                let condition = self.register_read(condition);
+
+                if condition == 0 {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.jump(address);
+                }
+            },
+            Opcode::Call => {
+                let address = self.next_register(thread_id);
+
+                let address = self.register_read(address);
+
+                self.instruction_call(thread_id, address);
+            },
+            Opcode::CallI => {
+                let address = self.next_const32(thread_id);
+
+                self.instruction_call(thread_id, address);
+            },
+            Opcode::Ret => {
+                self.instruction_ret(thread_id);
+            },
+            Opcode::Push => {
+                self.instruction_push(thread_id);
+            },
+            Opcode::Pop => {
+                self.instruction_pop(thread_id);
+            },
             Opcode::Wait => {
                 let lock_id = self.next_lock(thread_id);
 
                 if self.locked(lock_id) {
                     let thread = self.threads.get_mut(thread_id);
                     thread.wait(lock_id);
+                } else {
+                    self.race_acquire_lock(thread_id, lock_id);
+                }
+            },
+            Opcode::WaitAll => {
+                let mask = self.next_register(thread_id);
+
+                let mask = self.register_read(mask);
+
+                let locked = self.locked_mask(mask);
+                self.race_acquire(thread_id, mask & !locked);
+                if locked != 0 {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.wait_all(locked);
+                }
+            },
+            Opcode::WaitAny => {
+                let mask        = self.next_register(thread_id);
+                let destination = self.next_register(thread_id);
+
+                let mask = self.register_read(mask);
+
+                let unlocked = mask & !self.locked_mask(mask);
+                if unlocked != 0 {
+                    self.race_acquire(thread_id, unlocked & unlocked.wrapping_neg());
+                    self.register_write(destination, unlocked.trailing_zeros() as u64);
+                } else {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.wait_any(mask, destination);
                 }
             },
             Opcode::Lock => {
                 let lock_id = self.next_lock(thread_id);
 
-                self.callback(move |machine| {
-                    machine.lock(lock_id);
-                });
+                self.callback(Operation::Lock { lock_id });
             },
             Opcode::Unlock => {
                 let lock_id = self.next_lock(thread_id);
 
-                self.callback(move |machine| {
-                    machine.unlock(lock_id);
-                });
+                self.callback(Operation::Unlock { lock_id });
+            },
+            Opcode::SemInit => {
+                let semaphore_id = self.next_semaphore(thread_id);
+                let count        = self.next_register(thread_id);
+
+                let count = self.register_read(count);
+
+                self.callback(Operation::SemInit { semaphore_id, count });
+            },
+            Opcode::SemUp => {
+                let semaphore_id = self.next_semaphore(thread_id);
+
+                self.callback(Operation::SemUp { semaphore_id });
+            },
+            Opcode::SemDown => {
+                let semaphore_id = self.next_semaphore(thread_id);
+
+                if !self.semaphore_down(thread_id, semaphore_id) {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.wait_semaphore(semaphore_id);
+                }
+            },
+            Opcode::Barrier => {
+                let barrier_id = self.next_barrier(thread_id);
+                let count      = self.next_const8(thread_id);
+
+                self.barrier_arrive(thread_id, barrier_id, count as usize);
+            },
+            Opcode::Send => {
+                let channel_id = self.next_channel(thread_id);
+                let value      = self.next_register(thread_id);
+
+                let value = self.register_read(value);
+
+                if !self.channel_send(thread_id, channel_id, value) {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.wait_send(channel_id, value);
+                }
+            },
+            Opcode::Recv => {
+                let channel_id  = self.next_channel(thread_id);
+                let destination = self.next_register(thread_id);
+
+                if let Some(value) = self.channel_recv(thread_id, channel_id) {
+                    self.register_write(destination, value);
+                } else {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.wait_recv(channel_id, destination);
+                }
+            },
+            Opcode::TrySend => {
+                let channel_id = self.next_channel(thread_id);
+                let value      = self.next_register(thread_id);
+                let status     = self.next_register(thread_id);
+
+                let value = self.register_read(value);
+
+                let sent = self.channel_send(thread_id, channel_id, value);
+                self.register_write(status, if sent { 0 } else { 1 });
+            },
+            Opcode::TryRecv => {
+                let channel_id  = self.next_channel(thread_id);
+                let destination = self.next_register(thread_id);
+                let status      = self.next_register(thread_id);
+
+                let value = self.channel_recv(thread_id, channel_id);
+                if let Some(value) = value {
+                    self.register_write(destination, value);
+                }
+
+                self.register_write(status, if value.is_some() { 0 } else { 1 });
             },
             Opcode::Start => {
                 let other   = self.next_thread(thread_id);
@@ -218,30 +467,72 @@ impl<'a> Machine<'a> {
 
                 let address = self.register_read(address);
 
-                self.callback(move |machine| {
-                    let other = machine.threads.get_mut(other);
-                    other.jump(address);
-                    other.start();
-                });
+                self.callback(Operation::Start { thread_id: other, address });
             },
-            Opcode::Stop => {
+            Opcode::StartI => {
                 let other   = self.next_thread(thread_id);
+                let address = self.next_const32(thread_id);
 
-                self.callback(move |machine| {
-                    let other = machine.threads.get_mut(other);
-                    other.stop();
-                });
+                self.callback(Operation::Start { thread_id: other, address });
+            },
+            Opcode::Stop => {
+                let other = self.next_thread(thread_id);
+
+                self.callback(Operation::Stop { thread_id: other });
             },
             Opcode::Halt => {
-                let thread = self.threads.get_mut(thread_id);
+                self.thread_stop(thread_id);
+            },
+            Opcode::Tid => {
+                let destination = self.next_register(thread_id);
+
+                self.register_write(destination, ThreadId::to_raw(thread_id) as u64);
+            },
+            Opcode::TStatus => {
+                let other       = self.next_thread(thread_id);
+                let destination = self.next_register(thread_id);
+
+                let status = match self.threads.get(other).status() {
+                    ThreadStatus::Active   => 0,
+                    ThreadStatus::Inactive => 1,
+                    _                      => 2,
+                };
+
+                self.register_write(destination, status);
+            },
+            Opcode::Join => {
+                let other = self.next_thread(thread_id);
+
+                if !self.threads.get(other).is_inactive() {
+                    let thread = self.threads.get_mut(thread_id);
+                    thread.join(other);
+                }
+            },
+            Opcode::Spawn => {
+                let address     = self.next_register(thread_id);
+                let destination = self.next_register(thread_id);
+
+                let address = self.register_read(address);
 
-                thread.stop();
+                let other = self.threads.get_inactive();
+                if let Some(other) = other {
+                    self.thread_start(other, address);
+                }
+
+                self.register_write(destination, other.map_or(u64::MAX, |other| ThreadId::to_raw(other) as u64));
             },
             Opcode::Scan => {
                 let result = self.next_register(thread_id);
 
-                let mut input = String::new();
-                stdin().read_line(&mut input).unwrap_or_else(|_| self.error_input_read(thread_id));
+                let input = match self.capture.as_mut() {
+                    Some(capture) => capture.read_line().unwrap_or_else(|| self.error_input_read(thread_id)),
+                    None => {
+                        let mut input = String::new();
+                        stdin().read_line(&mut input).unwrap_or_else(|_| self.error_input_read(thread_id));
+                        input
+                    },
+                };
+
                 let integer = input.trim().parse::<u64>().unwrap_or_else(|_| self.error_input_parse(thread_id));
                 self.register_write(result, integer);
             },
@@ -250,7 +541,14 @@ impl<'a> Machine<'a> {
 
                 let value = self.register_read(value);
 
-                println!("{}", value);
+                self.print(format_args!("{}", value));
+            },
+            Opcode::FPrint => {
+                let value = self.next_register(thread_id);
+
+                let value = self.register_read(value);
+
+                self.print(format_args!("{:?}", f64::from_bits(value)));
             },
             Opcode::ProfileReset => {
                 self.instruction_profile_reset();
@@ -266,11 +564,23 @@ impl<'a> Machine<'a> {
 }
 
 impl Machine<'_> {
-    fn callback(&mut self, callback: impl Fn(&mut Machine) + 'static) {
-        self.callbacks.push((self.counter, Rc::new(callback)));
+    fn print(&mut self, line: Arguments) {
+        match self.capture.as_mut() {
+            Some(capture) => capture.write_line(line),
+            None          => println!("{}", line),
+        }
+    }
+
+    fn callback(&mut self, operation: Operation) {
+        self.callback_delay(0, operation);
     }
 
-    fn callback_delay(&mut self, delay: usize, callback: impl Fn(&mut Machine) + 'static) {
-        self.callbacks.push((self.counter + delay, Rc::new(callback)));
+    fn callback_delay(&mut self, delay: usize, operation: Operation) {
+        let delay = match self.random.as_mut() {
+            Some(random) if delay != 0 => delay + random.below(self.jitter + 1),
+            _ => delay,
+        };
+
+        self.callbacks.push(Callback::new(self.counter + delay, self.current.unwrap(), operation));
     }
 }
@@ -0,0 +1,114 @@
+use std::fmt::{ Display, Formatter };
+
+use architecture::BARRIERS_COUNT;
+
+use crate::machine::Machine;
+use crate::machine::race::Signal;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
+use crate::machine::thread::ThreadId;
+
+#[derive(Clone, Hash)]
+pub struct Barriers {
+    barriers: [Barrier; BARRIERS_COUNT],
+}
+
+impl Barriers {
+    pub fn new() -> Self {
+        Self {
+            barriers: [(); BARRIERS_COUNT].map(|_| Barrier::new()),
+        }
+    }
+
+    pub fn get_mut(&mut self, id: BarrierId) -> &mut Barrier {
+        &mut self.barriers[BarrierId::to_raw(id)]
+    }
+}
+
+#[derive(Clone, Hash)]
+pub struct Barrier {
+    arrived: usize,
+}
+
+impl Barrier {
+    pub fn new() -> Self {
+        Self {
+            arrived: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BarrierId(u8);
+
+impl BarrierId {
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        ((raw as usize) < BARRIERS_COUNT).then_some(Self(raw))
+    }
+
+    pub fn to_raw(id: BarrierId) -> usize {
+        id.0 as usize
+    }
+}
+
+impl Display for BarrierId {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("b")?;
+        formatter.write_fmt(format_args!("{}", self.0))?;
+        Ok(())
+    }
+}
+
+impl Snapshot for Barriers {
+    fn save(&self, writer: &mut Writer) {
+        self.barriers.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            barriers: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for Barrier {
+    fn save(&self, writer: &mut Writer) {
+        self.arrived.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            arrived: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for BarrierId {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self::from_raw(u8::restore(reader)).unwrap()
+    }
+}
+
+impl Machine<'_> {
+    pub fn barrier_arrive(&mut self, thread_id: ThreadId, barrier_id: BarrierId, count: usize) {
+        self.race_signal(thread_id, Signal::Barrier(barrier_id));
+        let barrier = self.barriers.get_mut(barrier_id);
+        barrier.arrived += 1;
+        if barrier.arrived < count {
+            self.threads.get_mut(thread_id).wait_barrier(barrier_id);
+            return;
+        }
+
+        barrier.arrived = 0;
+        self.race_wake(thread_id, Signal::Barrier(barrier_id));
+        for thread in self.threads.get_threads().iter().copied() {
+            if self.threads.get(thread).is_waiting_barrier(barrier_id) {
+                self.race_wake(thread, Signal::Barrier(barrier_id));
+                self.threads.get_mut(thread).start();
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use architecture::Opcode;
+
+use crate::machine::Machine;
+use crate::machine::lock::LockId;
+use crate::machine::operation::{ operation_atomic, operation_calcul, operation_load, operation_store, operation_unary };
+use crate::machine::register::{ RegisterId, RegisterRange };
+use crate::machine::semaphore::SemaphoreId;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
+use crate::machine::thread::{ Origin, ThreadId };
+
+#[derive(Clone, Hash)]
+pub struct Callback {
+    cycle: usize,
+    origin: Origin,
+    operation: Operation,
+}
+
+impl Callback {
+    pub fn new(cycle: usize, origin: Origin, operation: Operation) -> Self {
+        Self {
+            cycle,
+            origin,
+            operation,
+        }
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn profile_reset(&mut self, counter: usize) {
+        self.cycle -= counter;
+    }
+}
+
+#[derive(Clone, Hash)]
+pub enum Operation {
+    Load { opcode: Opcode, address: u64, destination: RegisterId, lock_id: LockId },
+    Store { opcode: Opcode, address: u64, value: u64, lock_id: LockId },
+    Ret { stack: u64, lock_id: LockId },
+    Unary { opcode: Opcode, a: u64, result: RegisterId, lock_id: LockId },
+    Calcul { opcode: Opcode, a: u64, b: u64, result: RegisterId, lock_id: LockId },
+    Value { value: u64, result: RegisterId, lock_id: LockId },
+    VectorCalcul { opcode: Opcode, values: Box<[(u64, u64)]>, result: RegisterRange, lock_id: LockId },
+    VectorLoad { address: u64, destination: RegisterRange, lock_id: LockId },
+    VectorStore { address: u64, values: Box<[u64]>, lock_id: LockId },
+    Atomic { opcode: Opcode, address: u64, value: u64, result: RegisterId, lock_id: LockId },
+    Cas { address: u64, expected: u64, desired: u64, result: RegisterId, lock_id: LockId },
+    Lock { lock_id: LockId },
+    Unlock { lock_id: LockId },
+    SemInit { semaphore_id: SemaphoreId, count: u64 },
+    SemUp { semaphore_id: SemaphoreId },
+    Start { thread_id: ThreadId, address: u64 },
+    Stop { thread_id: ThreadId },
+}
+
+impl Snapshot for Callback {
+    fn save(&self, writer: &mut Writer) {
+        self.cycle.save(writer);
+        self.origin.save(writer);
+        self.operation.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            cycle: Snapshot::restore(reader),
+            origin: Snapshot::restore(reader),
+            operation: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for Operation {
+    fn save(&self, writer: &mut Writer) {
+        match self {
+            Operation::Load { opcode, address, destination, lock_id } => {
+                writer.tag(0);
+                opcode.save(writer);
+                address.save(writer);
+                destination.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Store { opcode, address, value, lock_id } => {
+                writer.tag(1);
+                opcode.save(writer);
+                address.save(writer);
+                value.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Ret { stack, lock_id } => {
+                writer.tag(2);
+                stack.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Unary { opcode, a, result, lock_id } => {
+                writer.tag(3);
+                opcode.save(writer);
+                a.save(writer);
+                result.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Calcul { opcode, a, b, result, lock_id } => {
+                writer.tag(4);
+                opcode.save(writer);
+                a.save(writer);
+                b.save(writer);
+                result.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Value { value, result, lock_id } => {
+                writer.tag(5);
+                value.save(writer);
+                result.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::VectorCalcul { opcode, values, result, lock_id } => {
+                writer.tag(6);
+                opcode.save(writer);
+                values.save(writer);
+                result.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::VectorLoad { address, destination, lock_id } => {
+                writer.tag(7);
+                address.save(writer);
+                destination.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::VectorStore { address, values, lock_id } => {
+                writer.tag(8);
+                address.save(writer);
+                values.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Atomic { opcode, address, value, result, lock_id } => {
+                writer.tag(9);
+                opcode.save(writer);
+                address.save(writer);
+                value.save(writer);
+                result.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Cas { address, expected, desired, result, lock_id } => {
+                writer.tag(10);
+                address.save(writer);
+                expected.save(writer);
+                desired.save(writer);
+                result.save(writer);
+                lock_id.save(writer);
+            },
+            Operation::Lock { lock_id } => {
+                writer.tag(11);
+                lock_id.save(writer);
+            },
+            Operation::Unlock { lock_id } => {
+                writer.tag(12);
+                lock_id.save(writer);
+            },
+            Operation::SemInit { semaphore_id, count } => {
+                writer.tag(13);
+                semaphore_id.save(writer);
+                count.save(writer);
+            },
+            Operation::SemUp { semaphore_id } => {
+                writer.tag(14);
+                semaphore_id.save(writer);
+            },
+            Operation::Start { thread_id, address } => {
+                writer.tag(15);
+                thread_id.save(writer);
+                address.save(writer);
+            },
+            Operation::Stop { thread_id } => {
+                writer.tag(16);
+                thread_id.save(writer);
+            },
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        match reader.tag() {
+            0 => Operation::Load {
+                opcode: Snapshot::restore(reader),
+                address: Snapshot::restore(reader),
+                destination: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            1 => Operation::Store {
+                opcode: Snapshot::restore(reader),
+                address: Snapshot::restore(reader),
+                value: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            2 => Operation::Ret {
+                stack: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            3 => Operation::Unary {
+                opcode: Snapshot::restore(reader),
+                a: Snapshot::restore(reader),
+                result: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            4 => Operation::Calcul {
+                opcode: Snapshot::restore(reader),
+                a: Snapshot::restore(reader),
+                b: Snapshot::restore(reader),
+                result: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            5 => Operation::Value {
+                value: Snapshot::restore(reader),
+                result: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            6 => Operation::VectorCalcul {
+                opcode: Snapshot::restore(reader),
+                values: Snapshot::restore(reader),
+                result: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            7 => Operation::VectorLoad {
+                address: Snapshot::restore(reader),
+                destination: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            8 => Operation::VectorStore {
+                address: Snapshot::restore(reader),
+                values: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            9 => Operation::Atomic {
+                opcode: Snapshot::restore(reader),
+                address: Snapshot::restore(reader),
+                value: Snapshot::restore(reader),
+                result: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            10 => Operation::Cas {
+                address: Snapshot::restore(reader),
+                expected: Snapshot::restore(reader),
+                desired: Snapshot::restore(reader),
+                result: Snapshot::restore(reader),
+                lock_id: Snapshot::restore(reader),
+            },
+            11 => Operation::Lock {
+                lock_id: Snapshot::restore(reader),
+            },
+            12 => Operation::Unlock {
+                lock_id: Snapshot::restore(reader),
+            },
+            13 => Operation::SemInit {
+                semaphore_id: Snapshot::restore(reader),
+                count: Snapshot::restore(reader),
+            },
+            14 => Operation::SemUp {
+                semaphore_id: Snapshot::restore(reader),
+            },
+            15 => Operation::Start {
+                thread_id: Snapshot::restore(reader),
+                address: Snapshot::restore(reader),
+            },
+            16 => Operation::Stop {
+                thread_id: Snapshot::restore(reader),
+            },
+            _ => panic!(),
+        }
+    }
+}
+
+impl Machine<'_> {
+    pub fn callback_run(&mut self, callback: &Callback) {
+        let origin    = callback.origin;
+        let thread_id = origin.thread_id();
+        self.current  = Some(origin);
+        match &callback.operation {
+            Operation::Load { opcode, address, destination, lock_id } => {
+                let (_, closure) = operation_load(*opcode).unwrap();
+                let value = closure(self, origin, *address);
+                self.register_complete(*destination, value);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Store { opcode, address, value, lock_id } => {
+                let closure = operation_store(*opcode).unwrap();
+                closure(self, origin, *address, *value);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Ret { stack, lock_id } => {
+                let address = self.load64(origin, *stack);
+                self.threads.get_mut(thread_id).jump(address);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Unary { opcode, a, result, lock_id } => {
+                let (_, closure) = operation_unary(*opcode).unwrap();
+                let value = closure(self, origin, *a);
+                self.register_complete(*result, value);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Calcul { opcode, a, b, result, lock_id } => {
+                let (_, closure) = operation_calcul(*opcode).unwrap();
+                let value = closure(self, origin, *a, *b);
+                self.register_complete(*result, value);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Value { value, result, lock_id } => {
+                self.register_complete(*result, *value);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::VectorCalcul { opcode, values, result, lock_id } => {
+                let (_, closure) = operation_calcul(*opcode).unwrap();
+                for (lane, (a, b)) in values.iter().enumerate() {
+                    let value = closure(self, origin, *a, *b);
+                    self.register_complete(result.lane(lane), value);
+                }
+
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::VectorLoad { address, destination, lock_id } => {
+                for lane in 0 .. destination.lanes() {
+                    let value = self.load64(origin, address.wrapping_add(lane as u64 * 8));
+                    self.register_complete(destination.lane(lane), value);
+                }
+
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::VectorStore { address, values, lock_id } => {
+                for (lane, value) in values.iter().enumerate() {
+                    self.store64(origin, address.wrapping_add(lane as u64 * 8), *value);
+                }
+
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Atomic { opcode, address, value, result, lock_id } => {
+                let (_, closure) = operation_atomic(*opcode).unwrap();
+                let old = self.atomic_load64(origin, *address);
+                self.atomic_store64(origin, *address, closure(old, *value));
+                self.register_complete(*result, old);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Cas { address, expected, desired, result, lock_id } => {
+                let old = self.atomic_load64(origin, *address);
+                if old == *expected {
+                    self.atomic_store64(origin, *address, *desired);
+                }
+
+                self.register_complete(*result, old);
+                self.lock_complete(origin, *lock_id);
+            },
+            Operation::Lock { lock_id } => {
+                self.lock_access(origin, *lock_id);
+                self.lock(origin, *lock_id);
+            },
+            Operation::Unlock { lock_id } => {
+                self.lock_access(origin, *lock_id);
+                self.lock_release(origin, *lock_id);
+            },
+            Operation::SemInit { semaphore_id, count } => {
+                self.semaphore_init(thread_id, *semaphore_id, *count);
+            },
+            Operation::SemUp { semaphore_id } => {
+                self.semaphore_up(thread_id, *semaphore_id);
+            },
+            Operation::Start { thread_id, address } => {
+                self.thread_start_checked(origin, *thread_id, *address);
+            },
+            Operation::Stop { thread_id } => {
+                self.thread_stop_checked(origin, *thread_id);
+            },
+        }
+    }
+}
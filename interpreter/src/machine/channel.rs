@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+use std::fmt::{ Display, Formatter };
+
+use architecture::{ CHANNELS_COUNT, CHANNEL_CAPACITY };
+
+use crate::machine::Machine;
+use crate::machine::race::Signal;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
+use crate::machine::thread::ThreadId;
+
+#[derive(Clone, Hash)]
+pub struct Channels {
+    channels: [Channel; CHANNELS_COUNT],
+}
+
+impl Channels {
+    pub fn new() -> Self {
+        Self {
+            channels: [(); CHANNELS_COUNT].map(|_| Channel::new()),
+        }
+    }
+
+    pub fn get_mut(&mut self, id: ChannelId) -> &mut Channel {
+        &mut self.channels[ChannelId::to_raw(id)]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Channel> {
+        self.channels.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Channel> {
+        self.channels.iter_mut()
+    }
+}
+
+#[derive(Clone, Hash)]
+pub struct Channel {
+    values: VecDeque<u64>,
+    peak: usize,
+    sent: usize,
+}
+
+impl Channel {
+    pub fn new() -> Self {
+        Self {
+            values: VecDeque::with_capacity(CHANNEL_CAPACITY),
+            peak: 0,
+            sent: 0,
+        }
+    }
+
+    pub fn occupancy(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+
+    pub fn profile_reset(&mut self) {
+        self.peak = self.values.len();
+        self.sent = 0;
+    }
+
+    fn push(&mut self, value: u64) {
+        self.values.push_back(value);
+        self.peak = self.peak.max(self.values.len());
+        self.sent += 1;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelId(u8);
+
+impl ChannelId {
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        ((raw as usize) < CHANNELS_COUNT).then_some(Self(raw))
+    }
+
+    pub fn to_raw(id: ChannelId) -> usize {
+        id.0 as usize
+    }
+}
+
+impl Display for ChannelId {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("ch")?;
+        formatter.write_fmt(format_args!("{}", self.0))?;
+        Ok(())
+    }
+}
+
+impl Snapshot for Channels {
+    fn save(&self, writer: &mut Writer) {
+        self.channels.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            channels: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for Channel {
+    fn save(&self, writer: &mut Writer) {
+        self.values.save(writer);
+        self.peak.save(writer);
+        self.sent.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            values: Snapshot::restore(reader),
+            peak: Snapshot::restore(reader),
+            sent: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for ChannelId {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self::from_raw(u8::restore(reader)).unwrap()
+    }
+}
+
+impl Machine<'_> {
+    pub fn channel_send(&mut self, thread_id: ThreadId, channel_id: ChannelId, value: u64) -> bool {
+        let receiver = self.threads.iter().find_map(|thread| thread.waiting_recv(channel_id).map(|destination| (thread.id(), destination)));
+        if let Some((receiver, destination)) = receiver {
+            self.channels.get_mut(channel_id).sent += 1;
+            self.race_signal(thread_id, Signal::Channel(channel_id));
+            self.race_wake(receiver, Signal::Channel(channel_id));
+            self.threads.get_mut(receiver).start();
+            self.register_write_thread(receiver, destination, value);
+            return true;
+        }
+
+        let channel = self.channels.get_mut(channel_id);
+        if channel.values.len() >= CHANNEL_CAPACITY {
+            return false;
+        }
+
+        channel.push(value);
+        self.race_signal(thread_id, Signal::Channel(channel_id));
+        true
+    }
+
+    pub fn channel_recv(&mut self, thread_id: ThreadId, channel_id: ChannelId) -> Option<u64> {
+        let value = self.channels.get_mut(channel_id).values.pop_front()?;
+        self.race_wake(thread_id, Signal::Channel(channel_id));
+        let sender = self.threads.iter().find_map(|thread| thread.waiting_send(channel_id).map(|value| (thread.id(), value)));
+        if let Some((sender, other)) = sender {
+            self.channels.get_mut(channel_id).push(other);
+            self.race_signal(sender, Signal::Channel(channel_id));
+            self.threads.get_mut(sender).start();
+        }
+
+        Some(value)
+    }
+}
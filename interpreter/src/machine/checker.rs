@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{ Arguments, Write };
+use std::fs::write;
+use std::hash::{ Hash, Hasher };
+use std::io::{ BufRead, stdin };
+use std::panic::{ AssertUnwindSafe, catch_unwind, resume_unwind, set_hook, take_hook };
+use std::path::Path;
+use std::process::exit;
+use std::rc::Rc;
+
+use architecture::Opcode;
+
+use crate::machine::Machine;
+use crate::machine::callback::Callback;
+use crate::machine::schedule::Schedule;
+use crate::machine::thread::{ Thread, ThreadId };
+
+#[derive(Clone, Hash)]
+pub struct Capture {
+    input: Rc<[String]>,
+    position: usize,
+    output: String,
+}
+
+impl Capture {
+    fn new() -> Self {
+        Self {
+            input: stdin().lock().lines().map(Result::unwrap).collect(),
+            position: 0,
+            output: String::new(),
+        }
+    }
+
+    pub fn read_line(&mut self) -> Option<String> {
+        let line = self.input.get(self.position)?.clone();
+        self.position += 1;
+        Some(line)
+    }
+
+    pub fn write_line(&mut self, line: Arguments) {
+        writeln!(self.output, "{}", line).unwrap();
+    }
+}
+
+pub enum Outcome {
+    Error(String),
+    End,
+}
+
+#[derive(PartialEq, Eq)]
+enum Resource {
+    Output,
+    Threads,
+    Channel(u8),
+    Semaphore(u8),
+    Barrier(u8),
+}
+
+struct Checker {
+    reference: Option<(String, bool)>,
+    visited: HashSet<u64>,
+    schedules: usize,
+}
+
+impl Checker {
+    fn new() -> Self {
+        Self {
+            reference: None,
+            visited: HashSet::new(),
+            schedules: 0,
+        }
+    }
+
+    fn conclude(&mut self, machine: &Machine, outcome: Option<Outcome>, schedule: Schedule) -> Result<(), Failure> {
+        self.schedules += 1;
+        let finished = match outcome {
+            Some(Outcome::Error(message)) => return Err((message, schedule)),
+            Some(Outcome::End)            => true,
+            None                          => false,
+        };
+
+        let output = &machine.capture.as_ref().unwrap().output;
+        if self.diverges(output, finished) {
+            return Err((format!("ERROR (cycle {}): Output differs from the first explored schedule.", machine.counter), schedule));
+        }
+
+        Ok(())
+    }
+
+    fn diverges(&mut self, output: &str, finished: bool) -> bool {
+        let Some((reference, ended)) = self.reference.as_ref() else {
+            self.reference = Some((output.to_string(), finished));
+            return false;
+        };
+
+        match (finished, *ended) {
+            (true, true)   => output != reference,
+            (true, false)  => !output.starts_with(reference.as_str()),
+            (false, true)  => !reference.starts_with(output),
+            (false, false) => !output.starts_with(reference.as_str()) && !reference.starts_with(output),
+        }
+    }
+}
+
+type Failure = (String, Schedule);
+
+type Interleaving<'a> = (Machine<'a>, Vec<usize>);
+
+impl<'a> Machine<'a> {
+    pub fn check(mut self, bound: usize, path: &Path) {
+        self.capture = Some(Capture::new());
+
+        let hook = take_hook();
+        set_hook(Box::new(move |info| {
+            if !info.payload().is::<Outcome>() {
+                hook(info);
+            }
+        }));
+
+        let mut checker = Checker::new();
+        let schedule = Schedule::new(self.program.fingerprint());
+        let mut stack = vec![(self, schedule)];
+        while let Some((machine, schedule)) = stack.pop() {
+            if let Err((message, schedule)) = machine.check_cycle(&mut checker, schedule, bound, &mut stack) {
+                eprintln!("{}", message);
+                write(path, schedule.to_string()).unwrap();
+                println!("Schedule written to `{}` after {} explored schedules.", path.display(), checker.schedules);
+                exit(1);
+            }
+        }
+
+        println!("No failure in {} explored schedules of at most {} cycles.", checker.schedules, bound);
+    }
+
+    fn check_cycle(mut self, checker: &mut Checker, schedule: Schedule, bound: usize, stack: &mut Vec<(Machine<'a>, Schedule)>) -> Result<(), Failure> {
+        if self.threads.iter().all(Thread::is_inactive) {
+            return checker.conclude(&self, Some(Outcome::End), schedule);
+        }
+
+        let actives = match check_run(&mut self, Machine::cycle_start) {
+            Ok(actives)  => actives,
+            Err(outcome) => return checker.conclude(&self, Some(outcome), schedule),
+        };
+
+        let mut successors = Vec::new();
+        for order in self.check_orders(&actives) {
+            let mut machine = self.clone();
+            if let Err(outcome) = check_run(&mut machine, |machine| machine.cycle_threads(&order)) {
+                let schedule = check_step(&schedule, machine.elapsed, &actives, &order, &[]);
+                checker.conclude(&machine, Some(outcome), schedule)?;
+                continue;
+            }
+
+            let callbacks = machine.cycle_callbacks();
+            let branches = match machine.check_callbacks(&callbacks) {
+                Ok(branches) => branches,
+                Err((machine, outcome, permutation)) => {
+                    let schedule = check_step(&schedule, machine.elapsed, &actives, &order, &permutation);
+                    checker.conclude(&machine, Some(outcome), schedule)?;
+                    continue;
+                },
+            };
+
+            for (mut machine, permutation) in branches {
+                let schedule = check_step(&schedule, machine.elapsed, &actives, &order, &permutation);
+                machine.cycle_end();
+                if machine.elapsed >= bound {
+                    checker.conclude(&machine, None, schedule)?;
+                } else if checker.visited.insert(machine.fingerprint()) {
+                    successors.push((machine, schedule));
+                }
+            }
+        }
+
+        stack.extend(successors.into_iter().rev());
+        Ok(())
+    }
+
+    fn check_orders(&self, actives: &[ThreadId]) -> Vec<Box<[ThreadId]>> {
+        let mut groups: Vec<(Resource, Vec<usize>)> = Vec::new();
+        for (position, thread_id) in actives.iter().copied().enumerate() {
+            let Some(resource) = self.check_resource(thread_id) else {
+                continue;
+            };
+
+            match groups.iter_mut().find(|(other, _)| *other == resource) {
+                Some((_, positions)) => positions.push(position),
+                None                 => groups.push((resource, vec![position])),
+            }
+        }
+
+        let mut orders: Vec<Box<[ThreadId]>> = vec![Box::from(actives)];
+        for (_, positions) in groups.iter().filter(|(_, positions)| positions.len() > 1) {
+            orders = orders.into_iter()
+                .flat_map(|order| permutations(positions.len()).into_iter().map(move |permutation| {
+                    let mut permuted = order.clone();
+                    for (position, index) in positions.iter().zip(permutation.iter()) {
+                        permuted[*position] = order[positions[*index]];
+                    }
+
+                    permuted
+                }))
+                .collect();
+        }
+
+        orders
+    }
+
+    fn check_resource(&self, thread_id: ThreadId) -> Option<Resource> {
+        let cursor  = self.threads.get(thread_id).cursor();
+        let opcode  = Opcode::from_raw(self.program.get(cursor)?)?;
+        let operand = self.program.get(cursor + 1);
+        match opcode {
+            Opcode::Print | Opcode::FPrint | Opcode::Scan | Opcode::End     => Some(Resource::Output),
+            Opcode::Spawn | Opcode::Halt | Opcode::TStatus | Opcode::Join   => Some(Resource::Threads),
+            Opcode::Send | Opcode::Recv | Opcode::TrySend | Opcode::TryRecv => operand.map(Resource::Channel),
+            Opcode::SemDown                                                 => operand.map(Resource::Semaphore),
+            Opcode::Barrier                                                 => operand.map(Resource::Barrier),
+            _                                                               => None,
+        }
+    }
+
+    fn check_callbacks(self, callbacks: &[Callback]) -> Result<Vec<Interleaving<'a>>, (Box<Machine<'a>>, Outcome, Vec<usize>)> {
+        let mut level = vec![(self, Vec::new())];
+        for _ in 0 .. callbacks.len() {
+            let mut seen = HashSet::new();
+            let mut next = Vec::new();
+            for (machine, permutation) in level {
+                for index in (0 .. callbacks.len()).filter(|index| !permutation.contains(index)) {
+                    let mut branch = machine.clone();
+                    let mut permutation = permutation.clone();
+                    permutation.push(index);
+                    if let Err(outcome) = check_run(&mut branch, |branch| branch.callback_run(&callbacks[index])) {
+                        let remaining: Vec<usize> = (0 .. callbacks.len()).filter(|index| !permutation.contains(index)).collect();
+                        permutation.extend(remaining);
+                        return Err((Box::new(branch), outcome, permutation));
+                    }
+
+                    let mut done = permutation.clone();
+                    done.sort_unstable();
+                    if seen.insert((branch.fingerprint(), done)) {
+                        next.push((branch, permutation));
+                    }
+                }
+            }
+
+            level = next;
+        }
+
+        Ok(level)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.threads.hash(&mut hasher);
+        self.registers.hash(&mut hasher);
+        self.locks.hash(&mut hasher);
+        self.semaphores.hash(&mut hasher);
+        self.barriers.hash(&mut hasher);
+        self.channels.hash(&mut hasher);
+        self.memory.hash(&mut hasher);
+        self.races.hash(&mut hasher);
+        self.capture.hash(&mut hasher);
+        self.counter.hash(&mut hasher);
+        self.elapsed.hash(&mut hasher);
+
+        let mut callbacks: Vec<u64> = self.callbacks.iter().map(|callback| {
+            let mut hasher = DefaultHasher::new();
+            callback.hash(&mut hasher);
+            hasher.finish()
+        }).collect();
+
+        callbacks.sort_unstable();
+        callbacks.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn check_run<'a, T>(machine: &mut Machine<'a>, run: impl FnOnce(&mut Machine<'a>) -> T) -> Result<T, Outcome> {
+    catch_unwind(AssertUnwindSafe(|| run(machine))).map_err(|payload| match payload.downcast::<Outcome>() {
+        Ok(outcome)  => *outcome,
+        Err(payload) => resume_unwind(payload),
+    })
+}
+
+fn check_step(schedule: &Schedule, cycle: usize, actives: &[ThreadId], order: &[ThreadId], permutation: &[usize]) -> Schedule {
+    let mut schedule = schedule.clone();
+    if order != actives || permutation.iter().enumerate().any(|(index, other)| index != *other) {
+        schedule.insert(cycle, Box::from(order), Box::from(permutation));
+    }
+
+    schedule
+}
+
+fn permutations(count: usize) -> Vec<Box<[usize]>> {
+    let mut permutations = vec![Vec::new()];
+    for value in 0 .. count {
+        permutations = permutations.into_iter()
+            .flat_map(|permutation: Vec<usize>| (0 ..= permutation.len()).rev().map(move |position| {
+                let mut permutation = permutation.clone();
+                permutation.insert(position, value);
+                permutation
+            }))
+            .collect();
+    }
+
+    permutations.into_iter().map(Vec::into_boxed_slice).collect()
+}
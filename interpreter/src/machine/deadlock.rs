@@ -0,0 +1,107 @@
+use std::fmt::Write;
+
+use architecture::THREADS_COUNT;
+
+use crate::machine::Machine;
+use crate::machine::lock::{ LockId, lock_ids };
+use crate::machine::thread::{ ThreadId, ThreadStatus };
+
+impl Machine<'_> {
+    pub fn deadlock_check(&self) {
+        if !self.threads.get_actives().is_empty() {
+            return;
+        }
+
+        if self.threads.iter().any(|thread| self.deadlock_wakeable(thread.status())) {
+            return;
+        }
+
+        self.error_pause(&self.deadlock_report());
+    }
+
+    fn deadlock_wakeable(&self, status: ThreadStatus) -> bool {
+        match status {
+            ThreadStatus::Stalled             => true,
+            ThreadStatus::Waiting(mask)       => lock_ids(mask).all(|lock_id| self.locks.get(lock_id).pending() != 0),
+            ThreadStatus::WaitingAny(mask, _) => lock_ids(mask).any(|lock_id| self.locks.get(lock_id).pending() != 0),
+            _                                 => false,
+        }
+    }
+
+    fn deadlock_report(&self) -> String {
+        let mut report = String::new();
+        let mut waited = 0;
+        for thread in self.threads.iter().filter(|thread| !thread.is_inactive()) {
+            let address = self.origin(thread.id()).address();
+            write!(report, "\nThread `{}`, address {:#X}: {}.", thread.id(), address, thread.status()).unwrap();
+            if let ThreadStatus::Waiting(mask) | ThreadStatus::WaitingAny(mask, _) = thread.status() {
+                waited |= mask;
+            }
+        }
+
+        for lock_id in lock_ids(waited) {
+            let lock = self.locks.get(lock_id);
+            write!(report, "\nLock `{}`: ", lock_id).unwrap();
+            match lock.locker() {
+                Some(origin) => write!(report, "locked by thread `{}`, address {:#X}", origin.thread_id(), origin.address()).unwrap(),
+                None         => write!(report, "locked since the start").unwrap(),
+            }
+
+            match lock.pending() {
+                0       => write!(report, ", no pending operation will unlock it.").unwrap(),
+                pending => write!(report, ", {} pending operations will unlock it.", pending).unwrap(),
+            }
+        }
+
+        let mut explored = [false; THREADS_COUNT];
+        for thread in self.threads.iter().filter(|thread| !thread.is_inactive()) {
+            let mut path = Vec::new();
+            if let Some(start) = self.deadlock_search(&mut path, &mut explored, thread.id()) {
+                write!(report, "\nCycle: ").unwrap();
+                for (thread_id, lock_id) in path[start ..].iter() {
+                    write!(report, "`{}` -> ", thread_id).unwrap();
+                    if let Some(lock_id) = lock_id {
+                        write!(report, "`{}` -> ", lock_id).unwrap();
+                    }
+                }
+
+                write!(report, "`{}`.", path[start].0).unwrap();
+                break;
+            }
+        }
+
+        report
+    }
+
+    fn deadlock_search(&self, path: &mut Vec<(ThreadId, Option<LockId>)>, explored: &mut [bool; THREADS_COUNT], thread_id: ThreadId) -> Option<usize> {
+        if let Some(start) = path.iter().position(|(other, _)| *other == thread_id) {
+            return Some(start);
+        }
+
+        if explored[ThreadId::to_raw(thread_id)] {
+            return None;
+        }
+
+        for (lock_id, next) in self.deadlock_edges(thread_id) {
+            path.push((thread_id, lock_id));
+            if let Some(start) = self.deadlock_search(path, explored, next) {
+                return Some(start);
+            }
+
+            path.pop();
+        }
+
+        explored[ThreadId::to_raw(thread_id)] = true;
+        None
+    }
+
+    fn deadlock_edges(&self, thread_id: ThreadId) -> Vec<(Option<LockId>, ThreadId)> {
+        match self.threads.get(thread_id).status() {
+            ThreadStatus::Waiting(mask) | ThreadStatus::WaitingAny(mask, _) => lock_ids(mask)
+                .filter_map(|lock_id| self.locks.get(lock_id).locker().map(|origin| (Some(lock_id), origin.thread_id())))
+                .collect(),
+            ThreadStatus::WaitingJoin(other) => vec![(None, other)],
+            _ => Vec::new(),
+        }
+    }
+}
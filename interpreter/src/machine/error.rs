@@ -1,19 +1,60 @@
 use std::fmt::Arguments;
+use std::ops::Range;
+use std::panic::panic_any;
 use std::process::exit;
 
 use crate::machine::Machine;
+use crate::machine::checker::Outcome;
+use crate::machine::lock::LockId;
 use crate::machine::register::RegisterId;
-use crate::machine::thread::ThreadId;
+use crate::machine::thread::{ Origin, ThreadId };
+use crate::options::Hazards;
 
 impl Machine<'_> {
-    pub fn error_pause(&self) -> ! {
-        self.error(format_args!("No thread can continue."));
+    pub fn error_pause(&self, report: &str) -> ! {
+        self.error(format_args!("No thread can continue.{}", report));
     }
 
     pub fn error_data_race(&self, register_id: RegisterId) -> ! {
         self.error(format_args!("Data race on register `{}`.", register_id));
     }
 
+    pub fn error_memory_race(&self, origin: Origin, other: Origin, range: Range<usize>) -> ! {
+        self.error_origin(origin, format_args!("Data race on memory range {:#X}..{:#X} with thread `{}`, address {:#X}.", range.start, range.end, other.thread_id(), other.address()));
+    }
+
+    pub fn error_race_register(&self, origin: Origin, other: Origin, register_id: RegisterId) -> ! {
+        self.error_origin(origin, format_args!("Unordered access on register `{}` with thread `{}`, address {:#X}.", register_id, other.thread_id(), other.address()));
+    }
+
+    pub fn error_race_memory(&self, origin: Origin, other: Origin, range: Range<usize>) -> ! {
+        self.error_origin(origin, format_args!("Unordered access on memory range {:#X}..{:#X} with thread `{}`, address {:#X}.", range.start, range.end, other.thread_id(), other.address()));
+    }
+
+    pub fn hazard(&self, hazards: Hazards, origin: Origin, other: Origin, register_id: RegisterId, lock_id: LockId, write: bool) {
+        let access  = if write { "Write" } else { "Read" };
+        let thread  = other.thread_id();
+        let address = other.address();
+        let message = format_args!("{} of register `{}` before the completion of the operation issued by thread `{}`, address {:#X}, on lock `{}`.", access, register_id, thread, address, lock_id);
+        match hazards {
+            Hazards::Warning => self.warning_origin(origin, message),
+            Hazards::Error   => self.error_origin(origin, message),
+        }
+    }
+
+    pub fn error_schedule_threads(&self, actives: &[ThreadId]) -> ! {
+        let actives = actives.iter().map(|thread_id| format!("`{}`", thread_id)).collect::<Vec<_>>().join(", ");
+        self.error(format_args!("The schedule does not match the active threads of cycle {}: {}.", self.elapsed, actives));
+    }
+
+    pub fn error_schedule_callbacks(&self, count: usize) -> ! {
+        self.error(format_args!("The schedule does not match the {} operations completing in cycle {}.", count, self.elapsed));
+    }
+
+    pub fn error_zero_register(&self, origin: Origin) -> ! {
+        self.error_origin(origin, format_args!("Write on register `r0`."));
+    }
+
     pub fn error_program_address(&self, thread_id: ThreadId, address: u64) -> ! {
         self.error_thread(thread_id, format_args!("Address {:#X} is outside of the program bounds.", address));
     }
@@ -30,16 +71,70 @@ impl Machine<'_> {
         self.error_thread(thread_id, format_args!("Invalid register {}.", register));
     }
 
+    pub fn error_invalid_register_range(&self, thread_id: ThreadId, first: RegisterId, last: RegisterId) -> ! {
+        self.error_thread(thread_id, format_args!("Invalid register range {}..{}.", first, last));
+    }
+
     pub fn error_invalid_lock(&self, thread_id: ThreadId, lock: u8) -> ! {
         self.error_thread(thread_id, format_args!("Invalid lock {}.", lock));
     }
 
+    pub fn error_invalid_semaphore(&self, thread_id: ThreadId, semaphore: u8) -> ! {
+        self.error_thread(thread_id, format_args!("Invalid semaphore {}.", semaphore));
+    }
+
+    pub fn error_invalid_barrier(&self, thread_id: ThreadId, barrier: u8) -> ! {
+        self.error_thread(thread_id, format_args!("Invalid barrier {}.", barrier));
+    }
+
+    pub fn error_invalid_channel(&self, thread_id: ThreadId, channel: u8) -> ! {
+        self.error_thread(thread_id, format_args!("Invalid channel {}.", channel));
+    }
+
     pub fn error_invalid_thread(&self, thread_id: ThreadId, thread: u8) -> ! {
         self.error_thread(thread_id, format_args!("Invalid thread {}.", thread));
     }
 
-    pub fn error_division_by_zero(&self, thread_id: ThreadId) -> ! {
-        self.error_thread(thread_id, format_args!("Division by zero."));
+    pub fn error_division_by_zero(&self, origin: Origin) -> ! {
+        self.error_origin(origin, format_args!("Division by zero."));
+    }
+
+    pub fn error_arithmetic_overflow(&self, origin: Origin) -> ! {
+        self.error_origin(origin, format_args!("Arithmetic overflow."));
+    }
+
+    pub fn error_thread_active(&self, origin: Origin, thread_id: ThreadId) -> ! {
+        let thread = self.threads.get(thread_id);
+        self.error_origin(origin, format_args!("Start of thread `{}`, already active at address {:#X}.", thread.id(), thread.cursor()));
+    }
+
+    pub fn error_thread_pending(&self, origin: Origin, thread_id: ThreadId) -> ! {
+        let thread = self.threads.get(thread_id);
+        self.error_origin(origin, format_args!("Stop of thread `{}` at address {:#X}, with {} pending asynchronous operations.", thread.id(), thread.cursor(), thread.pending()));
+    }
+
+    pub fn error_lock_unlocked(&self, origin: Origin, lock_id: LockId) -> ! {
+        self.error_origin(origin, format_args!("Unlock of lock `{}`, already unlocked.", lock_id));
+    }
+
+    pub fn error_lock_unlocked_by(&self, origin: Origin, other: Origin, lock_id: LockId) -> ! {
+        self.error_origin(origin, format_args!("Unlock of lock `{}`, already unlocked by thread `{}`, address {:#X}.", lock_id, other.thread_id(), other.address()));
+    }
+
+    pub fn error_lock_conflict(&self, origin: Origin, other: Origin, lock_id: LockId) -> ! {
+        self.error_origin(origin, format_args!("Lock `{}` also accessed in this cycle by thread `{}`, address {:#X}.", lock_id, other.thread_id(), other.address()));
+    }
+
+    pub fn error_stack_overflow(&self, thread_id: ThreadId) -> ! {
+        self.error_thread(thread_id, format_args!("Stack overflow."));
+    }
+
+    pub fn error_stack_underflow(&self, thread_id: ThreadId) -> ! {
+        self.error_thread(thread_id, format_args!("Stack underflow."));
+    }
+
+    pub fn error_vector_lanes(&self, thread_id: ThreadId) -> ! {
+        self.error_thread(thread_id, format_args!("Vector operands have different lane counts."));
     }
 
     pub fn error_input_read(&self, thread_id: ThreadId) -> ! {
@@ -53,6 +148,10 @@ impl Machine<'_> {
 
 impl Machine<'_> {
     fn error(&self, message: Arguments) -> ! {
+        if self.capture.is_some() {
+            panic_any(Outcome::Error(format!("ERROR (cycle {}): {}", self.counter, message)));
+        }
+
         eprintln!("ERROR (cycle {}): {}", self.counter, message);
         exit(0);
     }
@@ -61,4 +160,16 @@ impl Machine<'_> {
         let thread = self.threads.get(thread_id);
         self.error(format_args!("In thread `{}`, address {:#X}. {}", thread.id(), thread.cursor(), message));
     }
+
+    fn warning_origin(&self, origin: Origin, message: Arguments) {
+        if self.capture.is_some() {
+            return;
+        }
+
+        eprintln!("WARNING (cycle {}): In thread `{}`, address {:#X}. {}", self.counter, origin.thread_id(), origin.address(), message);
+    }
+
+    fn error_origin(&self, origin: Origin, message: Arguments) -> ! {
+        self.error(format_args!("In thread `{}`, address {:#X}. {}", origin.thread_id(), origin.address(), message));
+    }
 }
@@ -1,11 +1,19 @@
+use std::panic::panic_any;
 use std::process::exit;
 use term_table::Table;
 use term_table::row::Row;
 use term_table::table_cell::TableCell;
 
+use architecture::Opcode;
+
 use crate::machine::Machine;
+use crate::machine::callback::Operation;
+use crate::machine::checker::Outcome;
+use crate::machine::lock::LockId;
+use crate::machine::operation::{ operation_load, operation_unary, operation_calcul, operation_atomic, bit_extract, bit_insert };
+use crate::machine::register::RegisterId;
 use crate::machine::thread::ThreadId;
-use crate::time::{ TIME_LOAD, TIME_STORE };
+use crate::time::{ TIME_STORE, TIME_PUSH, TIME_POP, TIME_CALL, TIME_RET, TIME_BFX, TIME_BFI, TIME_SELECT, TIME_VLOAD, TIME_VSTORE, TIME_CAS };
 
 impl Machine<'_> {
     pub fn instruction_const(&mut self, thread: ThreadId, closure: impl Fn(&mut Machine, ThreadId) -> u64) {
@@ -16,70 +24,342 @@ impl Machine<'_> {
         self.register_write(register, constant);
     }
 
-    pub fn instruction_load(&mut self, thread_id: ThreadId, closure: fn(&Machine, ThreadId, u64) -> u64) {
+    pub fn instruction_load(&mut self, thread_id: ThreadId, opcode: Opcode) {
         let address     = self.next_register(thread_id);
         let destination = self.next_register(thread_id);
         let lock_id     = self.next_lock(thread_id);
 
-        let address = self.register_read(address);
-        self.lock(lock_id);
+        self.issue_load(thread_id, address, 0, destination, lock_id, opcode);
+    }
+
+    pub fn instruction_load_offset(&mut self, thread_id: ThreadId, opcode: Opcode) {
+        let address     = self.next_register(thread_id);
+        let offset      = self.next_immediate(thread_id, 2);
+        let destination = self.next_register(thread_id);
+        let lock_id     = self.next_lock(thread_id);
 
-        self.callback_delay(TIME_LOAD, move |machine| {
-            let value = closure(machine, thread_id, address);
-            machine.register_write(destination, value);
-            machine.unlock(lock_id);
-        });
+        self.issue_load(thread_id, address, offset, destination, lock_id, opcode);
     }
 
-    pub fn instruction_store(&mut self, thread_id: ThreadId, closure: fn(&mut Machine, ThreadId, u64, u64)) {
+    pub fn instruction_store(&mut self, thread_id: ThreadId, opcode: Opcode) {
         let source      = self.next_register(thread_id);
         let destination = self.next_register(thread_id);
         let lock_id     = self.next_lock(thread_id);
 
-        let address = self.register_read(destination);
-        let value   = self.register_read(source);
-        self.lock(lock_id);
+        self.issue_store(thread_id, source, destination, 0, lock_id, opcode);
+    }
 
-        self.callback_delay(TIME_STORE, move |machine| {
-            closure(machine, thread_id, address, value);
-            machine.unlock(lock_id);
-        });
+    pub fn instruction_store_offset(&mut self, thread_id: ThreadId, opcode: Opcode) {
+        let source      = self.next_register(thread_id);
+        let destination = self.next_register(thread_id);
+        let offset      = self.next_immediate(thread_id, 2);
+        let lock_id     = self.next_lock(thread_id);
+
+        self.issue_store(thread_id, source, destination, offset, lock_id, opcode);
     }
 
-    pub fn instruction_calcul(&mut self, thread_id: ThreadId, delay: usize, closure: fn(&Machine, ThreadId, u64, u64) -> u64) {
+    pub fn instruction_unary(&mut self, thread_id: ThreadId, opcode: Opcode) {
+        let a       = self.next_register(thread_id);
+        let result  = self.next_register(thread_id);
+        let lock_id = self.next_lock(thread_id);
+
+        self.issue_unary(thread_id, a, result, lock_id, opcode);
+    }
+
+    pub fn instruction_calcul(&mut self, thread_id: ThreadId, opcode: Opcode) {
         let a       = self.next_register(thread_id);
         let b       = self.next_register(thread_id);
         let result  = self.next_register(thread_id);
         let lock_id = self.next_lock(thread_id);
 
+        self.issue_calcul(thread_id, a, b, result, lock_id, opcode);
+    }
+
+    pub fn instruction_calcul_immediate(&mut self, thread_id: ThreadId, opcode: Opcode, size: usize) {
+        let a         = self.next_register(thread_id);
+        let immediate = self.next_immediate(thread_id, size);
+        let result    = self.next_register(thread_id);
+        let lock_id   = self.next_lock(thread_id);
+
+        self.issue_calcul_immediate(thread_id, a, immediate, result, lock_id, opcode);
+    }
+
+    pub fn issue_load(&mut self, thread_id: ThreadId, address: RegisterId, offset: u64, destination: RegisterId, lock_id: LockId, opcode: Opcode) {
+        let (delay, _) = operation_load(opcode).unwrap();
+        let address = self.register_read(address).wrapping_add(offset);
+        self.register_issue(destination, lock_id);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(delay, Operation::Load { opcode, address, destination, lock_id });
+    }
+
+    pub fn issue_store(&mut self, thread_id: ThreadId, source: RegisterId, destination: RegisterId, offset: u64, lock_id: LockId, opcode: Opcode) {
+        let address = self.register_read(destination).wrapping_add(offset);
+        let value   = self.register_read(source);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(TIME_STORE, Operation::Store { opcode, address, value, lock_id });
+    }
+
+    pub fn instruction_call(&mut self, thread_id: ThreadId, address: u64) {
+        let lock_id = self.next_lock(thread_id);
+
+        let stack = self.stack_push(thread_id);
+        let value = self.threads.get(thread_id).cursor();
+        self.lock_issue(thread_id, lock_id);
+
+        self.threads.get_mut(thread_id).jump(address);
+
+        self.callback_delay(TIME_CALL, Operation::Store { opcode: Opcode::Store64, address: stack, value, lock_id });
+    }
+
+    pub fn instruction_ret(&mut self, thread_id: ThreadId) {
+        let lock_id = self.next_lock(thread_id);
+
+        let stack = self.stack_pop(thread_id);
+        self.lock_issue(thread_id, lock_id);
+
+        if !lock_id.is_synchronous() {
+            self.threads.get_mut(thread_id).wait(lock_id);
+        }
+
+        self.callback_delay(TIME_RET, Operation::Ret { stack, lock_id });
+    }
+
+    pub fn instruction_push(&mut self, thread_id: ThreadId) {
+        let source  = self.next_register(thread_id);
+        let lock_id = self.next_lock(thread_id);
+
+        self.issue_push(thread_id, source, lock_id);
+    }
+
+    pub fn instruction_pop(&mut self, thread_id: ThreadId) {
+        let destination = self.next_register(thread_id);
+        let lock_id     = self.next_lock(thread_id);
+
+        self.issue_pop(thread_id, destination, lock_id);
+    }
+
+    pub fn issue_push(&mut self, thread_id: ThreadId, source: RegisterId, lock_id: LockId) {
+        let value = self.register_read(source);
+        let stack = self.stack_push(thread_id);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(TIME_PUSH, Operation::Store { opcode: Opcode::Store64, address: stack, value, lock_id });
+    }
+
+    pub fn issue_pop(&mut self, thread_id: ThreadId, destination: RegisterId, lock_id: LockId) {
+        let stack = self.stack_pop(thread_id);
+        self.register_issue(destination, lock_id);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(TIME_POP, Operation::Load { opcode: Opcode::Load64, address: stack, destination, lock_id });
+    }
+
+    pub fn instruction_vector_calcul(&mut self, thread_id: ThreadId, opcode: Opcode) {
+        let a       = self.next_register_range(thread_id);
+        let b       = self.next_register_range(thread_id);
+        let result  = self.next_register_range(thread_id);
+        let lock_id = self.next_lock(thread_id);
+
+        if a.lanes() != result.lanes() || b.lanes() != result.lanes() {
+            self.error_vector_lanes(thread_id);
+        }
+
+        let values: Box<[(u64, u64)]> = (0 .. result.lanes())
+            .map(|lane| (self.register_read(a.lane(lane)), self.register_read(b.lane(lane))))
+            .collect();
+
+        let (delay, _) = operation_calcul(opcode).unwrap();
+        for lane in 0 .. result.lanes() {
+            self.register_issue(result.lane(lane), lock_id);
+        }
+
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(delay + result.lanes() - 1, Operation::VectorCalcul { opcode, values, result, lock_id });
+    }
+
+    pub fn instruction_vector_load(&mut self, thread_id: ThreadId) {
+        let address     = self.next_register(thread_id);
+        let destination = self.next_register_range(thread_id);
+        let lock_id     = self.next_lock(thread_id);
+
+        let address = self.register_read(address);
+        for lane in 0 .. destination.lanes() {
+            self.register_issue(destination.lane(lane), lock_id);
+        }
+
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(TIME_VLOAD + destination.lanes() - 1, Operation::VectorLoad { address, destination, lock_id });
+    }
+
+    pub fn instruction_vector_store(&mut self, thread_id: ThreadId) {
+        let source      = self.next_register_range(thread_id);
+        let destination = self.next_register(thread_id);
+        let lock_id     = self.next_lock(thread_id);
+
+        let values: Box<[u64]> = (0 .. source.lanes())
+            .map(|lane| self.register_read(source.lane(lane)))
+            .collect();
+
+        let address = self.register_read(destination);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(TIME_VSTORE + source.lanes() - 1, Operation::VectorStore { address, values, lock_id });
+    }
+
+    pub fn instruction_atomic(&mut self, thread_id: ThreadId, opcode: Opcode) {
+        let address = self.next_register(thread_id);
+        let value   = self.next_register(thread_id);
+        let result  = self.next_register(thread_id);
+        let lock_id = self.next_lock(thread_id);
+
+        let (delay, _) = operation_atomic(opcode).unwrap();
+        let address = self.register_read(address);
+        let value   = self.register_read(value);
+        self.register_issue(result, lock_id);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(delay, Operation::Atomic { opcode, address, value, result, lock_id });
+    }
+
+    pub fn instruction_cas(&mut self, thread_id: ThreadId) {
+        let address  = self.next_register(thread_id);
+        let expected = self.next_register(thread_id);
+        let desired  = self.next_register(thread_id);
+        let result   = self.next_register(thread_id);
+        let lock_id  = self.next_lock(thread_id);
+
+        let address  = self.register_read(address);
+        let expected = self.register_read(expected);
+        let desired  = self.register_read(desired);
+        self.register_issue(result, lock_id);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(TIME_CAS, Operation::Cas { address, expected, desired, result, lock_id });
+    }
+
+    pub fn instruction_bit_extract(&mut self, thread_id: ThreadId) {
+        let a        = self.next_register(thread_id);
+        let position = self.get_8(thread_id);
+        let width    = self.get_8(thread_id);
+        let result   = self.next_register(thread_id);
+        let lock_id  = self.next_lock(thread_id);
+
+        self.issue_bit_extract(thread_id, a, position, width, result, lock_id);
+    }
+
+    pub fn instruction_bit_insert(&mut self, thread_id: ThreadId) {
+        let a        = self.next_register(thread_id);
+        let b        = self.next_register(thread_id);
+        let position = self.get_8(thread_id);
+        let width    = self.get_8(thread_id);
+        let result   = self.next_register(thread_id);
+        let lock_id  = self.next_lock(thread_id);
+
+        self.issue_bit_insert(thread_id, a, b, position, width, result, lock_id);
+    }
+
+    pub fn instruction_select(&mut self, thread_id: ThreadId) {
+        let condition = self.next_register(thread_id);
+        let a         = self.next_register(thread_id);
+        let b         = self.next_register(thread_id);
+        let result    = self.next_register(thread_id);
+        let lock_id   = self.next_lock(thread_id);
+
+        self.issue_select(thread_id, condition, a, b, result, lock_id);
+    }
+
+    pub fn issue_unary(&mut self, thread_id: ThreadId, a: RegisterId, result: RegisterId, lock_id: LockId, opcode: Opcode) {
+        let (delay, _) = operation_unary(opcode).unwrap();
+        let a = self.register_read(a);
+
+        self.issue_result(thread_id, result, lock_id, delay, Operation::Unary { opcode, a, result, lock_id });
+    }
+
+    pub fn issue_calcul(&mut self, thread_id: ThreadId, a: RegisterId, b: RegisterId, result: RegisterId, lock_id: LockId, opcode: Opcode) {
+        let (delay, _) = operation_calcul(opcode).unwrap();
         let a = self.register_read(a);
         let b = self.register_read(b);
-        self.lock(lock_id);
 
-        self.callback_delay(delay, move |machine| {
-            let value = closure(machine, thread_id, a, b);
-            machine.register_write(result, value);
-            machine.unlock(lock_id);
-        });
+        self.issue_result(thread_id, result, lock_id, delay, Operation::Calcul { opcode, a, b, result, lock_id });
+    }
+
+    pub fn issue_calcul_immediate(&mut self, thread_id: ThreadId, a: RegisterId, immediate: u64, result: RegisterId, lock_id: LockId, opcode: Opcode) {
+        let (delay, _) = operation_calcul(opcode).unwrap();
+        let a = self.register_read(a);
+
+        self.issue_result(thread_id, result, lock_id, delay, Operation::Calcul { opcode, a, b: immediate, result, lock_id });
+    }
+
+    pub fn issue_bit_extract(&mut self, thread_id: ThreadId, a: RegisterId, position: u8, width: u8, result: RegisterId, lock_id: LockId) {
+        let value = bit_extract(self.register_read(a), position, width);
+
+        self.issue_result(thread_id, result, lock_id, TIME_BFX, Operation::Value { value, result, lock_id });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn issue_bit_insert(&mut self, thread_id: ThreadId, a: RegisterId, b: RegisterId, position: u8, width: u8, result: RegisterId, lock_id: LockId) {
+        let a = self.register_read(a);
+        let b = self.register_read(b);
+        let value = bit_insert(a, b, position, width);
+
+        self.issue_result(thread_id, result, lock_id, TIME_BFI, Operation::Value { value, result, lock_id });
+    }
+
+    pub fn issue_select(&mut self, thread_id: ThreadId, condition: RegisterId, a: RegisterId, b: RegisterId, result: RegisterId, lock_id: LockId) {
+        let condition = self.register_read(condition);
+        let a         = self.register_read(a);
+        let b         = self.register_read(b);
+
+        let value = if condition == 0 { a } else { b };
+
+        self.issue_result(thread_id, result, lock_id, TIME_SELECT, Operation::Value { value, result, lock_id });
+    }
+
+    pub fn issue_result(&mut self, thread_id: ThreadId, result: RegisterId, lock_id: LockId, delay: usize, operation: Operation) {
+        self.register_issue(result, lock_id);
+        self.lock_issue(thread_id, lock_id);
+
+        self.callback_delay(delay, operation);
     }
 
     pub fn instruction_profile_reset(&mut self) {
         for callback in self.callbacks.iter_mut() {
-            callback.0 -= self.counter;
+            callback.profile_reset(self.counter);
         }
 
         self.counter = 0;
         for thread in self.threads.iter_mut() {
             thread.profile_reset();
         }
+
+        for channel in self.channels.iter_mut() {
+            channel.profile_reset();
+        }
     }
 
     pub fn instruction_profile_dump(&mut self) {
+        if self.capture.is_some() {
+            return;
+        }
+
         let mut table = Table::new();
-        table.add_row(Row::new(vec!["Thread", "Active time", "Inactive time", "Wait time"]));
+        table.add_row(Row::new(vec!["Thread", "Active time", "Inactive time", "Wait time", "Semaphore time", "Barrier time", "Channel time", "Stall time"]));
         for (i, thread) in self.threads.iter().enumerate() {
             let profile = thread.profile();
-            let mut row = Row::new(vec![thread.id().to_string(), profile.active().to_string(), profile.inactive().to_string(), profile.waiting().to_string()]);
+            let mut row = Row::new(vec![
+                thread.id().to_string(),
+                profile.active().to_string(),
+                profile.inactive().to_string(),
+                profile.waiting().to_string(),
+                profile.waiting_semaphore().to_string(),
+                profile.waiting_barrier().to_string(),
+                profile.waiting_channel().to_string(),
+                profile.stalled().to_string(),
+            ]);
             if i != 0 {
                 row.has_separator = false;
             }
@@ -87,11 +367,34 @@ impl Machine<'_> {
             table.add_row(row);
         }
 
-        table.add_row(Row::new(vec![TableCell::new_with_col_span(format!("Cycles: {}", self.counter), 4)]));
+        table.add_row(Row::new(vec![TableCell::new_with_col_span(format!("Cycles: {}", self.counter), 8)]));
         println!("{}", table.render());
+
+        let mut table = Table::new();
+        table.add_row(Row::new(vec!["Channel", "Occupancy", "Peak occupancy", "Sent"]));
+        for (i, channel) in self.channels.iter().enumerate() {
+            if channel.sent() == 0 && channel.occupancy() == 0 {
+                continue;
+            }
+
+            let mut row = Row::new(vec![format!("ch{}", i), channel.occupancy().to_string(), channel.peak().to_string(), channel.sent().to_string()]);
+            if table.rows.len() != 1 {
+                row.has_separator = false;
+            }
+
+            table.add_row(row);
+        }
+
+        if table.rows.len() != 1 {
+            println!("{}", table.render());
+        }
     }
 
     pub fn instruction_end(&self) -> ! {
+        if self.capture.is_some() {
+            panic_any(Outcome::End);
+        }
+
         exit(0);
     }
 }
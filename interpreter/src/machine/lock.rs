@@ -1,7 +1,12 @@
+use std::fmt::{ Display, Formatter };
+
 use architecture::LOCKS_COUNT;
 
 use crate::machine::Machine;
+use crate::machine::thread::{ Origin, ThreadId };
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
 
+#[derive(Clone, Hash)]
 pub struct Locks {
     locks: [Lock; LOCKS_COUNT],
 }
@@ -22,19 +27,36 @@ impl Locks {
     }
 }
 
+#[derive(Clone, Hash)]
 pub struct Lock {
     locked: bool,
+    locker: Option<Origin>,
+    unlocked: Option<Origin>,
+    access: Option<(Origin, usize)>,
+    pending: usize,
 }
 
 impl Lock {
     pub fn new() -> Self {
         Self {
             locked: true,
+            locker: None,
+            unlocked: None,
+            access: None,
+            pending: 0,
         }
     }
+
+    pub fn locker(&self) -> Option<Origin> {
+        self.locker
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LockId(u8);
 
 impl LockId {
@@ -45,6 +67,86 @@ impl LockId {
     pub fn to_raw(id: LockId) -> usize {
         id.0 as usize
     }
+
+    pub fn is_synchronous(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Display for LockId {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("l")?;
+        formatter.write_fmt(format_args!("{}", self.0))?;
+        Ok(())
+    }
+}
+
+pub fn lock_mask(lock_id: LockId) -> u64 {
+    1 << LockId::to_raw(lock_id)
+}
+
+pub fn lock_ids(mask: u64) -> impl Iterator<Item = LockId> {
+    (0 .. LOCKS_COUNT as u8)
+        .filter(move |index| mask & (1 << index) != 0)
+        .map(LockId)
+}
+
+pub struct LockMask(pub u64);
+
+impl Display for LockMask {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, lock_id) in lock_ids(self.0).enumerate() {
+            if index != 0 {
+                formatter.write_str(", ")?;
+            }
+
+            formatter.write_fmt(format_args!("`{}`", lock_id))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Snapshot for Locks {
+    fn save(&self, writer: &mut Writer) {
+        self.locks.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            locks: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for Lock {
+    fn save(&self, writer: &mut Writer) {
+        self.locked.save(writer);
+        self.locker.save(writer);
+        self.unlocked.save(writer);
+        self.access.save(writer);
+        self.pending.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            locked: Snapshot::restore(reader),
+            locker: Snapshot::restore(reader),
+            unlocked: Snapshot::restore(reader),
+            access: Snapshot::restore(reader),
+            pending: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for LockId {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self::from_raw(u8::restore(reader)).unwrap()
+    }
 }
 
 impl Machine<'_> {
@@ -52,16 +154,83 @@ impl Machine<'_> {
         self.locks.get(lock_id).locked
     }
 
-    pub fn lock(&mut self, lock_id: LockId) {
-        self.locks.get_mut(lock_id).locked = true;
+    pub fn locked_mask(&self, mask: u64) -> u64 {
+        (0 .. LOCKS_COUNT)
+            .filter(|index| mask & (1 << index) != 0 && self.locks.locks[*index].locked)
+            .fold(0, |locked, index| locked | (1 << index))
+    }
+
+    pub fn lock(&mut self, origin: Origin, lock_id: LockId) {
+        let lock = self.locks.get_mut(lock_id);
+        lock.locked = true;
+        lock.locker = Some(origin);
+        lock.unlocked = None;
+    }
+
+    pub fn lock_access(&mut self, origin: Origin, lock_id: LockId) {
+        let counter = self.counter;
+        let lock = self.locks.get_mut(lock_id);
+        let access = lock.access.replace((origin, counter));
+        if !self.strict {
+            return;
+        }
+
+        if let Some((other, cycle)) = access {
+            if cycle == counter && other.thread_id() != origin.thread_id() {
+                self.error_lock_conflict(origin, other, lock_id);
+            }
+        }
+    }
+
+    pub fn lock_release(&mut self, origin: Origin, lock_id: LockId) {
+        let lock = self.locks.get(lock_id);
+        if self.strict && !lock.locked {
+            match lock.unlocked {
+                Some(other) => self.error_lock_unlocked_by(origin, other, lock_id),
+                None        => self.error_lock_unlocked(origin, lock_id),
+            }
+        }
+
+        self.unlock(lock_id);
+        self.locks.get_mut(lock_id).unlocked = Some(origin);
+    }
+
+    pub fn lock_issue(&mut self, thread_id: ThreadId, lock_id: LockId) {
+        self.threads.get_mut(thread_id).issue();
+        if lock_id.is_synchronous() {
+            self.threads.get_mut(thread_id).stall();
+        } else {
+            let origin = self.origin(thread_id);
+            self.lock(origin, lock_id);
+            self.locks.get_mut(lock_id).pending += 1;
+        }
+    }
+
+    pub fn lock_complete(&mut self, origin: Origin, lock_id: LockId) {
+        let thread_id = origin.thread_id();
+        self.threads.get_mut(thread_id).complete();
+        if lock_id.is_synchronous() {
+            let thread = self.threads.get_mut(thread_id);
+            if thread.is_stalled() {
+                thread.start();
+            }
+        } else {
+            self.lock_access(origin, lock_id);
+            self.locks.get_mut(lock_id).pending -= 1;
+            self.unlock(lock_id);
+        }
     }
 
     pub fn unlock(&mut self, lock_id: LockId) {
+        self.race_release(lock_id);
         self.locks.get_mut(lock_id).locked = false;
         for thread in self.threads.get_threads().into_iter().copied() {
-            let thread = self.threads.get_mut(thread);
-            if thread.is_waiting(lock_id) {
-                thread.start();
+            if self.threads.get(thread).is_waiting(lock_id) {
+                self.race_acquire_lock(thread, lock_id);
+            }
+
+            if let Some(destination) = self.threads.get_mut(thread).notify(lock_id) {
+                self.register_write_thread(thread, destination, LockId::to_raw(lock_id) as u64);
             }
         }
     }
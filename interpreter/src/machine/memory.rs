@@ -1,70 +1,178 @@
+use std::hash::{ Hash, Hasher };
 use std::ops::Range;
 
+use architecture::THREADS_COUNT;
+
 use crate::machine::Machine;
-use crate::machine::thread::ThreadId;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
+use crate::machine::thread::{ Origin, ThreadId };
 
 const MEMORY_SIZE: usize = 0x10000;
+const STACK_SIZE: usize = 0x400;
 
+#[derive(Clone)]
 pub struct Memory {
     bytes: [u8; MEMORY_SIZE],
+    stack_pointers: [u64; THREADS_COUNT],
+    accesses: Vec<MemoryAccess>,
 }
 
 impl Memory {
     pub fn new() -> Self {
         Self {
             bytes: [0; MEMORY_SIZE],
+            stack_pointers: [0; THREADS_COUNT],
+            accesses: Vec::new(),
         }
     }
+
+    pub fn reset(&mut self) {
+        self.accesses.clear();
+    }
+
+    fn stack_top(thread_id: ThreadId) -> u64 {
+        (MEMORY_SIZE - ThreadId::to_raw(thread_id) * STACK_SIZE) as u64
+    }
+
+    fn stack_bottom(thread_id: ThreadId) -> u64 {
+        Self::stack_top(thread_id) - STACK_SIZE as u64
+    }
+}
+
+impl Hash for Memory {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state);
+        self.stack_pointers.hash(state);
+    }
+}
+
+#[derive(Clone)]
+pub struct MemoryAccess {
+    origin: Origin,
+    range: Range<usize>,
+    write: bool,
+    atomic: bool,
+}
+
+impl Snapshot for Memory {
+    fn save(&self, writer: &mut Writer) {
+        writer.bytes(&self.bytes);
+        self.stack_pointers.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        let mut memory = Self::new();
+        memory.bytes.copy_from_slice(reader.bytes(MEMORY_SIZE));
+        memory.stack_pointers = Snapshot::restore(reader);
+        memory
+    }
 }
 
 impl Machine<'_> {
-    pub fn load8(&self, thread_id: ThreadId, address: u64) -> u8 {
-        u8::from_ne_bytes(self.load_x(thread_id, address, 1).try_into().unwrap())
+    pub fn load8(&mut self, origin: Origin, address: u64) -> u8 {
+        u8::from_ne_bytes(self.load_x(origin, address, 1, false).try_into().unwrap())
+    }
+
+    pub fn load16(&mut self, origin: Origin, address: u64) -> u16 {
+        u16::from_ne_bytes(self.load_x(origin, address, 2, false).try_into().unwrap())
     }
 
-    pub fn load16(&self, thread_id: ThreadId, address: u64) -> u16 {
-        u16::from_ne_bytes(self.load_x(thread_id, address, 2).try_into().unwrap())
+    pub fn load32(&mut self, origin: Origin, address: u64) -> u32 {
+        u32::from_ne_bytes(self.load_x(origin, address, 4, false).try_into().unwrap())
     }
 
-    pub fn load32(&self, thread_id: ThreadId, address: u64) -> u32 {
-        u32::from_ne_bytes(self.load_x(thread_id, address, 4).try_into().unwrap())
+    pub fn load64(&mut self, origin: Origin, address: u64) -> u64 {
+        u64::from_ne_bytes(self.load_x(origin, address, 8, false).try_into().unwrap())
     }
 
-    pub fn load64(&self, thread_id: ThreadId, address: u64) -> u64 {
-        u64::from_ne_bytes(self.load_x(thread_id, address, 8).try_into().unwrap())
+    pub fn store8(&mut self, origin: Origin, address: u64, value: u8) {
+        self.store_x(origin, address, 1, &value.to_ne_bytes(), false);
     }
 
-    pub fn store8(&mut self, thread_id: ThreadId, address: u64, value: u8) {
-        self.store_x(thread_id, address, 1, &value.to_ne_bytes());
+    pub fn store16(&mut self, origin: Origin, address: u64, value: u16) {
+        self.store_x(origin, address, 2, &value.to_ne_bytes(), false);
     }
 
-    pub fn store16(&mut self, thread_id: ThreadId, address: u64, value: u16) {
-        self.store_x(thread_id, address, 2, &value.to_ne_bytes());
+    pub fn store32(&mut self, origin: Origin, address: u64, value: u32) {
+        self.store_x(origin, address, 4, &value.to_ne_bytes(), false);
     }
 
-    pub fn store32(&mut self, thread_id: ThreadId, address: u64, value: u32) {
-        self.store_x(thread_id, address, 4, &value.to_ne_bytes());
+    pub fn store64(&mut self, origin: Origin, address: u64, value: u64) {
+        self.store_x(origin, address, 8, &value.to_ne_bytes(), false);
     }
 
-    pub fn store64(&mut self, thread_id: ThreadId, address: u64, value: u64) {
-        self.store_x(thread_id, address, 8, &value.to_ne_bytes());
+    pub fn atomic_load64(&mut self, origin: Origin, address: u64) -> u64 {
+        u64::from_ne_bytes(self.load_x(origin, address, 8, true).try_into().unwrap())
+    }
+
+    pub fn atomic_store64(&mut self, origin: Origin, address: u64, value: u64) {
+        self.store_x(origin, address, 8, &value.to_ne_bytes(), true);
+    }
+
+    pub fn stack_reset(&mut self, thread_id: ThreadId) {
+        self.memory.stack_pointers[ThreadId::to_raw(thread_id)] = Memory::stack_top(thread_id);
+    }
+
+    pub fn stack_push(&mut self, thread_id: ThreadId) -> u64 {
+        let stack_pointer = self.memory.stack_pointers[ThreadId::to_raw(thread_id)];
+        if stack_pointer < Memory::stack_bottom(thread_id) + 8 {
+            self.error_stack_overflow(thread_id);
+        }
+
+        self.memory.stack_pointers[ThreadId::to_raw(thread_id)] = stack_pointer - 8;
+        stack_pointer - 8
+    }
+
+    pub fn stack_pop(&mut self, thread_id: ThreadId) -> u64 {
+        let stack_pointer = self.memory.stack_pointers[ThreadId::to_raw(thread_id)];
+        if stack_pointer + 8 > Memory::stack_top(thread_id) {
+            self.error_stack_underflow(thread_id);
+        }
+
+        self.memory.stack_pointers[ThreadId::to_raw(thread_id)] = stack_pointer + 8;
+        stack_pointer
     }
 }
 
 impl Machine<'_> {
-    fn load_x(&self, thread_id: ThreadId, address: u64, length: usize) -> &[u8] {
-        self.memory.bytes.get(Self::get_range(address, length))
-            .unwrap_or_else(|| self.error_memory_address(thread_id, address))
+    fn load_x(&mut self, origin: Origin, address: u64, length: usize, atomic: bool) -> &[u8] {
+        let Some(range) = Self::get_range(address, length) else {
+            self.error_memory_address(origin.thread_id(), address);
+        };
+
+        self.memory_access(origin, range.clone(), false, atomic);
+        &self.memory.bytes[range]
     }
 
-    fn store_x(&mut self, thread_id: ThreadId, address: u64, length: usize, value: &[u8]) {
-        match self.memory.bytes.get_mut(Self::get_range(address, length)) {
-            Some(slice) => slice.copy_from_slice(value),
-            None => self.error_memory_address(thread_id, address),
+    fn store_x(&mut self, origin: Origin, address: u64, length: usize, value: &[u8], atomic: bool) {
+        let Some(range) = Self::get_range(address, length) else {
+            self.error_memory_address(origin.thread_id(), address);
+        };
+
+        self.memory_access(origin, range.clone(), true, atomic);
+        self.memory.bytes[range].copy_from_slice(value);
+    }
+
+    fn memory_access(&mut self, origin: Origin, range: Range<usize>, write: bool, atomic: bool) {
+        for access in self.memory.accesses.iter() {
+            if access.origin.thread_id() == origin.thread_id() || !(write || access.write) || (atomic && access.atomic) {
+                continue;
+            }
+
+            let start = range.start.max(access.range.start);
+            let end   = range.end.min(access.range.end);
+            if start < end {
+                self.error_memory_race(origin, access.origin, start .. end);
+            }
         }
+
+        self.race_memory(range.clone(), write, atomic);
+        self.memory.accesses.push(MemoryAccess { origin, range, write, atomic });
     }
 
-    fn get_range(address: u64, length: usize) -> Range<usize> {
-        address as usize .. address as usize + length
+    fn get_range(address: u64, length: usize) -> Option<Range<usize>> {
+        let start = address as usize;
+        let end   = start.checked_add(length)?;
+        (end <= MEMORY_SIZE).then_some(start .. end)
     }
 }
@@ -0,0 +1,212 @@
+use architecture::Opcode;
+
+use crate::machine::Machine;
+use crate::machine::thread::Origin;
+use crate::time::*;
+
+pub type Load = fn(&mut Machine, Origin, u64) -> u64;
+pub type Store = fn(&mut Machine, Origin, u64, u64);
+pub type Unary = fn(&Machine, Origin, u64) -> u64;
+pub type Calcul = fn(&Machine, Origin, u64, u64) -> u64;
+pub type Atomic = fn(u64, u64) -> u64;
+
+pub fn operation_immediate(opcode: Opcode) -> Option<(Opcode, usize)> {
+    Some(match opcode {
+        Opcode::AndI8      => (Opcode::And,     1),
+        Opcode::AndI16     => (Opcode::And,     2),
+        Opcode::AndI32     => (Opcode::And,     4),
+        Opcode::OrI8       => (Opcode::Or,      1),
+        Opcode::OrI16      => (Opcode::Or,      2),
+        Opcode::OrI32      => (Opcode::Or,      4),
+        Opcode::XorI8      => (Opcode::Xor,     1),
+        Opcode::XorI16     => (Opcode::Xor,     2),
+        Opcode::XorI32     => (Opcode::Xor,     4),
+        Opcode::ShiftLI8   => (Opcode::ShiftL,  1),
+        Opcode::ShiftLI16  => (Opcode::ShiftL,  2),
+        Opcode::ShiftLI32  => (Opcode::ShiftL,  4),
+        Opcode::ShiftRI8   => (Opcode::ShiftR,  1),
+        Opcode::ShiftRI16  => (Opcode::ShiftR,  2),
+        Opcode::ShiftRI32  => (Opcode::ShiftR,  4),
+        Opcode::ShiftRAI8  => (Opcode::ShiftRA, 1),
+        Opcode::ShiftRAI16 => (Opcode::ShiftRA, 2),
+        Opcode::ShiftRAI32 => (Opcode::ShiftRA, 4),
+        Opcode::AddI8      => (Opcode::Add,     1),
+        Opcode::AddI16     => (Opcode::Add,     2),
+        Opcode::AddI32     => (Opcode::Add,     4),
+        Opcode::SubI8      => (Opcode::Sub,     1),
+        Opcode::SubI16     => (Opcode::Sub,     2),
+        Opcode::SubI32     => (Opcode::Sub,     4),
+        Opcode::MulI8      => (Opcode::Mul,     1),
+        Opcode::MulI16     => (Opcode::Mul,     2),
+        Opcode::MulI32     => (Opcode::Mul,     4),
+        _ => return None,
+    })
+}
+
+pub fn operation_offset(opcode: Opcode) -> Option<Opcode> {
+    Some(match opcode {
+        Opcode::Load8O   => Opcode::Load8,
+        Opcode::Load16O  => Opcode::Load16,
+        Opcode::Load32O  => Opcode::Load32,
+        Opcode::Load64O  => Opcode::Load64,
+        Opcode::Load8SO  => Opcode::Load8S,
+        Opcode::Load16SO => Opcode::Load16S,
+        Opcode::Load32SO => Opcode::Load32S,
+        Opcode::Store8O  => Opcode::Store8,
+        Opcode::Store16O => Opcode::Store16,
+        Opcode::Store32O => Opcode::Store32,
+        Opcode::Store64O => Opcode::Store64,
+        _ => return None,
+    })
+}
+
+pub fn operation_vector(opcode: Opcode) -> Option<Opcode> {
+    Some(match opcode {
+        Opcode::VAdd  => Opcode::Add,
+        Opcode::VSub  => Opcode::Sub,
+        Opcode::VMul  => Opcode::Mul,
+        Opcode::VAnd  => Opcode::And,
+        Opcode::VOr   => Opcode::Or,
+        Opcode::VXor  => Opcode::Xor,
+        Opcode::VFAdd => Opcode::FAdd,
+        Opcode::VFSub => Opcode::FSub,
+        Opcode::VFMul => Opcode::FMul,
+        Opcode::VFDiv => Opcode::FDiv,
+        _ => return None,
+    })
+}
+
+pub fn operation_load(opcode: Opcode) -> Option<(usize, Load)> {
+    Some(match opcode {
+        Opcode::Load8   => (TIME_LOAD,    |machine, origin, address| machine.load8(origin, address) as u64),
+        Opcode::Load16  => (TIME_LOAD,    |machine, origin, address| machine.load16(origin, address) as u64),
+        Opcode::Load32  => (TIME_LOAD,    |machine, origin, address| machine.load32(origin, address) as u64),
+        Opcode::Load64  => (TIME_LOAD,    |machine, origin, address| machine.load64(origin, address)),
+        Opcode::Load8S  => (TIME_LOAD8S,  |machine, origin, address| machine.load8(origin, address) as i8 as u64),
+        Opcode::Load16S => (TIME_LOAD16S, |machine, origin, address| machine.load16(origin, address) as i16 as u64),
+        Opcode::Load32S => (TIME_LOAD32S, |machine, origin, address| machine.load32(origin, address) as i32 as u64),
+        _ => return None,
+    })
+}
+
+pub fn operation_store(opcode: Opcode) -> Option<Store> {
+    Some(match opcode {
+        Opcode::Store8  => |machine, origin, address, value| machine.store8(origin, address, value as u8),
+        Opcode::Store16 => |machine, origin, address, value| machine.store16(origin, address, value as u16),
+        Opcode::Store32 => |machine, origin, address, value| machine.store32(origin, address, value as u32),
+        Opcode::Store64 => |machine, origin, address, value| machine.store64(origin, address, value),
+        _ => return None,
+    })
+}
+
+pub fn operation_atomic(opcode: Opcode) -> Option<(usize, Atomic)> {
+    Some(match opcode {
+        Opcode::Xchg     => (TIME_XCHG,     |_, value| value),
+        Opcode::FetchAdd => (TIME_FETCHADD, |old, value| old.wrapping_add(value)),
+        Opcode::FetchAnd => (TIME_FETCHAND, |old, value| old & value),
+        Opcode::FetchOr  => (TIME_FETCHOR,  |old, value| old | value),
+        _ => return None,
+    })
+}
+
+pub fn operation_unary(opcode: Opcode) -> Option<(usize, Unary)> {
+    Some(match opcode {
+        Opcode::Not    => (TIME_NOT,    |_, _, a| !a),
+        Opcode::PopCnt => (TIME_POPCNT, |_, _, a| a.count_ones() as u64),
+        Opcode::Clz    => (TIME_CLZ,    |_, _, a| a.leading_zeros() as u64),
+        Opcode::Ctz    => (TIME_CTZ,    |_, _, a| a.trailing_zeros() as u64),
+        Opcode::BSwap  => (TIME_BSWAP,  |_, _, a| a.swap_bytes()),
+        Opcode::FSqrt  => (TIME_FSQRT,  |_, _, a| f64::from_bits(a).sqrt().to_bits()),
+        Opcode::IToF   => (TIME_ITOF,   |_, _, a| (a as i64 as f64).to_bits()),
+        Opcode::FToI   => (TIME_FTOI,   |_, _, a| f64::from_bits(a) as i64 as u64),
+        _ => return None,
+    })
+}
+
+pub fn operation_calcul(opcode: Opcode) -> Option<(usize, Calcul)> {
+    Some(match opcode {
+        Opcode::And     => (TIME_AND,  |_, _, a, b| a & b),
+        Opcode::Or      => (TIME_OR,   |_, _, a, b| a | b),
+        Opcode::Xor     => (TIME_XOR,  |_, _, a, b| a ^ b),
+        Opcode::AndN    => (TIME_ANDN, |_, _, a, b| a & !b),
+        Opcode::RotL    => (TIME_ROTL, |_, _, a, b| a.rotate_left((b % 64) as u32)),
+        Opcode::RotR    => (TIME_ROTR, |_, _, a, b| a.rotate_right((b % 64) as u32)),
+        Opcode::ShiftL  => (TIME_SHL,  |_, _, a, b| if b < 64 { a << b } else { 0 }),
+        Opcode::ShiftR  => (TIME_SHR,  |_, _, a, b| if b < 64 { a >> b } else { 0 }),
+        Opcode::ShiftRA => (TIME_SRA,  |_, _, a, b| ((a as i64) >> b.min(63)) as u64),
+        Opcode::Add     => (TIME_ADD,  |_, _, a, b| a.wrapping_add(b)),
+        Opcode::Sub     => (TIME_SUB,  |_, _, a, b| a.wrapping_sub(b)),
+        Opcode::Mul     => (TIME_MUL,  |_, _, a, b| a.wrapping_mul(b)),
+        Opcode::MulH    => (TIME_MULH, |_, _, a, b| ((a as u128 * b as u128) >> 64) as u64),
+        Opcode::AddC    => (TIME_ADDC, |machine, origin, a, b| {
+            a.checked_add(b).unwrap_or_else(|| machine.error_arithmetic_overflow(origin))
+        }),
+        Opcode::SubC    => (TIME_SUBC, |machine, origin, a, b| {
+            a.checked_sub(b).unwrap_or_else(|| machine.error_arithmetic_overflow(origin))
+        }),
+        Opcode::MulC    => (TIME_MULC, |machine, origin, a, b| {
+            a.checked_mul(b).unwrap_or_else(|| machine.error_arithmetic_overflow(origin))
+        }),
+        Opcode::Div     => (TIME_DIV,  |machine, origin, a, b| {
+            if b == 0 {
+                machine.error_division_by_zero(origin);
+            }
+
+            a / b
+        }),
+        Opcode::Rem     => (TIME_REM,  |machine, origin, a, b| {
+            if b == 0 {
+                machine.error_division_by_zero(origin);
+            }
+
+            a % b
+        }),
+        Opcode::SDiv    => (TIME_SDIV, |machine, origin, a, b| {
+            if b == 0 {
+                machine.error_division_by_zero(origin);
+            }
+
+            (a as i64).wrapping_div(b as i64) as u64
+        }),
+        Opcode::SRem    => (TIME_SREM, |machine, origin, a, b| {
+            if b == 0 {
+                machine.error_division_by_zero(origin);
+            }
+
+            (a as i64).wrapping_rem(b as i64) as u64
+        }),
+        Opcode::Eq      => (TIME_EQ,   |_, _, a, b| if a == b { 0 } else { 1 }),
+        Opcode::Lt      => (TIME_LT,   |_, _, a, b| if a < b { 0 } else { 1 }),
+        Opcode::Gt      => (TIME_GT,   |_, _, a, b| if a > b { 0 } else { 1 }),
+        Opcode::Ne      => (TIME_NE,   |_, _, a, b| if a != b { 0 } else { 1 }),
+        Opcode::Le      => (TIME_LE,   |_, _, a, b| if a <= b { 0 } else { 1 }),
+        Opcode::Ge      => (TIME_GE,   |_, _, a, b| if a >= b { 0 } else { 1 }),
+        Opcode::SLt     => (TIME_SLT,  |_, _, a, b| if (a as i64) < (b as i64) { 0 } else { 1 }),
+        Opcode::SGt     => (TIME_SGT,  |_, _, a, b| if (a as i64) > (b as i64) { 0 } else { 1 }),
+        Opcode::FAdd    => (TIME_FADD, |_, _, a, b| (f64::from_bits(a) + f64::from_bits(b)).to_bits()),
+        Opcode::FSub    => (TIME_FSUB, |_, _, a, b| (f64::from_bits(a) - f64::from_bits(b)).to_bits()),
+        Opcode::FMul    => (TIME_FMUL, |_, _, a, b| (f64::from_bits(a) * f64::from_bits(b)).to_bits()),
+        Opcode::FDiv    => (TIME_FDIV, |_, _, a, b| (f64::from_bits(a) / f64::from_bits(b)).to_bits()),
+        Opcode::FMin    => (TIME_FMIN, |_, _, a, b| f64::from_bits(a).min(f64::from_bits(b)).to_bits()),
+        Opcode::FMax    => (TIME_FMAX, |_, _, a, b| f64::from_bits(a).max(f64::from_bits(b)).to_bits()),
+        Opcode::FEq     => (TIME_FEQ,  |_, _, a, b| if f64::from_bits(a) == f64::from_bits(b) { 0 } else { 1 }),
+        Opcode::FLt     => (TIME_FLT,  |_, _, a, b| if f64::from_bits(a) < f64::from_bits(b) { 0 } else { 1 }),
+        Opcode::FGt     => (TIME_FGT,  |_, _, a, b| if f64::from_bits(a) > f64::from_bits(b) { 0 } else { 1 }),
+        Opcode::FLe     => (TIME_FLE,  |_, _, a, b| if f64::from_bits(a) <= f64::from_bits(b) { 0 } else { 1 }),
+        Opcode::FGe     => (TIME_FGE,  |_, _, a, b| if f64::from_bits(a) >= f64::from_bits(b) { 0 } else { 1 }),
+        _ => return None,
+    })
+}
+
+pub fn bit_extract(a: u64, position: u8, width: u8) -> u64 {
+    a.checked_shr(position as u32).unwrap_or(0) & bit_mask(width)
+}
+
+pub fn bit_insert(a: u64, b: u64, position: u8, width: u8) -> u64 {
+    let mask = bit_mask(width).checked_shl(position as u32).unwrap_or(0);
+    (a & !mask) | (b.checked_shl(position as u32).unwrap_or(0) & mask)
+}
+
+fn bit_mask(width: u8) -> u64 {
+    1u64.checked_shl(width as u32).map_or(u64::MAX, |bit| bit - 1)
+}
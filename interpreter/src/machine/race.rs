@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use architecture::{ BARRIERS_COUNT, CHANNELS_COUNT, LOCKS_COUNT, REGISTERS_COUNT, SEMAPHORES_COUNT, THREADS_COUNT };
+
+use crate::machine::Machine;
+use crate::machine::barrier::BarrierId;
+use crate::machine::channel::ChannelId;
+use crate::machine::lock::{ LockId, lock_mask };
+use crate::machine::register::RegisterId;
+use crate::machine::semaphore::SemaphoreId;
+use crate::machine::thread::{ Origin, ThreadId };
+
+type Clock = [u64; THREADS_COUNT];
+
+#[derive(Clone, Hash)]
+pub struct Races {
+    threads: [Clock; THREADS_COUNT],
+    locks: [Clock; LOCKS_COUNT],
+    semaphores: [Clock; SEMAPHORES_COUNT],
+    barriers: [Clock; BARRIERS_COUNT],
+    channels: [Clock; CHANNELS_COUNT],
+    registers: Box<[Shadow]>,
+    memory: BTreeMap<usize, Shadow>,
+}
+
+impl Races {
+    pub fn new() -> Self {
+        let mut threads = [[0; THREADS_COUNT]; THREADS_COUNT];
+        for (index, clock) in threads.iter_mut().enumerate() {
+            clock[index] = 1;
+        }
+
+        Self {
+            threads,
+            locks: [[0; THREADS_COUNT]; LOCKS_COUNT],
+            semaphores: [[0; THREADS_COUNT]; SEMAPHORES_COUNT],
+            barriers: [[0; THREADS_COUNT]; BARRIERS_COUNT],
+            channels: [[0; THREADS_COUNT]; CHANNELS_COUNT],
+            registers: (0 .. REGISTERS_COUNT).map(|_| Shadow::new()).collect(),
+            memory: BTreeMap::new(),
+        }
+    }
+
+    fn signal(&mut self, signal: Signal) -> &mut Clock {
+        match signal {
+            Signal::Semaphore(semaphore_id) => &mut self.semaphores[SemaphoreId::to_raw(semaphore_id)],
+            Signal::Barrier(barrier_id)     => &mut self.barriers[BarrierId::to_raw(barrier_id)],
+            Signal::Channel(channel_id)     => &mut self.channels[ChannelId::to_raw(channel_id)],
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Signal {
+    Semaphore(SemaphoreId),
+    Barrier(BarrierId),
+    Channel(ChannelId),
+}
+
+#[derive(Clone, Copy, Hash)]
+struct Access {
+    origin: Origin,
+    epoch: u64,
+    atomic: bool,
+}
+
+impl Access {
+    fn ordered(self, clock: &Clock) -> bool {
+        self.epoch <= clock[ThreadId::to_raw(self.origin.thread_id())]
+    }
+}
+
+#[derive(Clone, Copy, Hash)]
+struct Shadow {
+    write: Option<Access>,
+    reads: [Option<Access>; THREADS_COUNT],
+}
+
+impl Shadow {
+    fn new() -> Self {
+        Self {
+            write: None,
+            reads: [None; THREADS_COUNT],
+        }
+    }
+
+    fn conflict(&self, clock: &Clock, write: bool, atomic: bool) -> Option<Origin> {
+        let unordered = |access: &Access| !access.ordered(clock) && !(atomic && access.atomic);
+        if let Some(access) = self.write.filter(unordered) {
+            return Some(access.origin);
+        }
+
+        if !write {
+            return None;
+        }
+
+        self.reads.iter()
+            .flatten()
+            .find(|access| unordered(access))
+            .map(|access| access.origin)
+    }
+
+    fn record(&mut self, origin: Origin, clock: &Clock, write: bool, atomic: bool) {
+        let thread = ThreadId::to_raw(origin.thread_id());
+        let access = Access { origin, epoch: clock[thread], atomic };
+        if write {
+            self.write = Some(access);
+            self.reads = [None; THREADS_COUNT];
+        } else {
+            self.reads[thread] = Some(access);
+        }
+    }
+}
+
+fn clock_join(clock: &mut Clock, other: &Clock) {
+    for (value, other) in clock.iter_mut().zip(other.iter()) {
+        *value = (*value).max(*other);
+    }
+}
+
+impl Machine<'_> {
+    pub fn race_release(&mut self, lock_id: LockId) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let Some(origin) = self.current else {
+            return;
+        };
+
+        let thread = ThreadId::to_raw(origin.thread_id());
+        let clock = races.threads[thread];
+        clock_join(&mut races.locks[LockId::to_raw(lock_id)], &clock);
+        races.threads[thread][thread] += 1;
+    }
+
+    pub fn race_acquire(&mut self, thread_id: ThreadId, mask: u64) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        for index in (0 .. LOCKS_COUNT).filter(|index| mask & (1 << index) != 0) {
+            let clock = races.locks[index];
+            clock_join(&mut races.threads[ThreadId::to_raw(thread_id)], &clock);
+        }
+    }
+
+    pub fn race_acquire_lock(&mut self, thread_id: ThreadId, lock_id: LockId) {
+        self.race_acquire(thread_id, lock_mask(lock_id));
+    }
+
+    pub fn race_signal(&mut self, thread_id: ThreadId, signal: Signal) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let thread = ThreadId::to_raw(thread_id);
+        let clock = races.threads[thread];
+        clock_join(races.signal(signal), &clock);
+        races.threads[thread][thread] += 1;
+    }
+
+    pub fn race_wake(&mut self, thread_id: ThreadId, signal: Signal) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let clock = *races.signal(signal);
+        clock_join(&mut races.threads[ThreadId::to_raw(thread_id)], &clock);
+    }
+
+    pub fn race_fork(&mut self, thread_id: ThreadId) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let Some(origin) = self.current else {
+            return;
+        };
+
+        let parent = ThreadId::to_raw(origin.thread_id());
+        let clock = races.threads[parent];
+        clock_join(&mut races.threads[ThreadId::to_raw(thread_id)], &clock);
+        races.threads[parent][parent] += 1;
+    }
+
+    pub fn race_join(&mut self, thread_id: ThreadId, other: ThreadId) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let clock = races.threads[ThreadId::to_raw(other)];
+        clock_join(&mut races.threads[ThreadId::to_raw(thread_id)], &clock);
+    }
+
+    pub fn race_register(&mut self, register_id: RegisterId, write: bool) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let Some(origin) = self.current else {
+            return;
+        };
+
+        let clock = races.threads[ThreadId::to_raw(origin.thread_id())];
+        let shadow = &mut races.registers[register_id.to_raw()];
+        if let Some(other) = shadow.conflict(&clock, write, false) {
+            self.error_race_register(origin, other, register_id);
+        }
+
+        shadow.record(origin, &clock, write, false);
+    }
+
+    pub fn race_memory(&mut self, range: Range<usize>, write: bool, atomic: bool) {
+        let Some(races) = self.races.as_mut() else {
+            return;
+        };
+
+        let Some(origin) = self.current else {
+            return;
+        };
+
+        let clock = races.threads[ThreadId::to_raw(origin.thread_id())];
+        let mut conflict: Option<(Origin, Range<usize>)> = None;
+        for address in range {
+            let shadow = races.memory.entry(address).or_insert_with(Shadow::new);
+            match (shadow.conflict(&clock, write, atomic), &mut conflict) {
+                (Some(other), None) => conflict = Some((other, address .. address + 1)),
+                (Some(other), Some((first, bytes))) if first.thread_id() == other.thread_id() && bytes.end == address => bytes.end += 1,
+                _ => shadow.record(origin, &clock, write, atomic),
+            }
+        }
+
+        if let Some((other, bytes)) = conflict {
+            self.error_race_memory(origin, other, bytes);
+        }
+    }
+}
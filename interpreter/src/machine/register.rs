@@ -3,7 +3,11 @@ use std::fmt::{ Display, Formatter };
 use architecture::REGISTERS_COUNT;
 
 use crate::machine::Machine;
+use crate::machine::lock::LockId;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
+use crate::machine::thread::{ Origin, ThreadId };
 
+#[derive(Clone, Hash)]
 pub struct Registers {
     registers: Box<[Register]>,
 }
@@ -21,7 +25,6 @@ impl Registers {
         }
     }
 
-    #[allow(dead_code)]
     fn get(&self, id: RegisterId) -> &Register {
         &self.registers[RegisterId::to_raw(id)]
     }
@@ -31,15 +34,17 @@ impl Registers {
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum RegisterStatus {
     None,
     Read,
     Write,
 }
 
+#[derive(Clone, Hash)]
 pub struct Register {
     status: RegisterStatus,
+    pending: Option<(Origin, LockId)>,
     value: u64,
 }
 
@@ -47,12 +52,13 @@ impl Register {
     pub fn new() -> Self {
         Self {
             status: RegisterStatus::None,
+            pending: None,
             value: 0,
         }
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RegisterId(u8);
 
 impl RegisterId {
@@ -63,6 +69,30 @@ impl RegisterId {
     pub fn to_raw(self) -> usize {
         self.0 as usize
     }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[derive(Clone, Copy, Hash)]
+pub struct RegisterRange {
+    first: u8,
+    last: u8,
+}
+
+impl RegisterRange {
+    pub fn new(first: RegisterId, last: RegisterId) -> Option<Self> {
+        (first.0 <= last.0).then_some(Self { first: first.0, last: last.0 })
+    }
+
+    pub fn lanes(self) -> usize {
+        (self.last - self.first) as usize + 1
+    }
+
+    pub fn lane(self, index: usize) -> RegisterId {
+        RegisterId(self.first + index as u8)
+    }
 }
 
 impl Display for RegisterId {
@@ -73,8 +103,84 @@ impl Display for RegisterId {
     }
 }
 
+impl Snapshot for Registers {
+    fn save(&self, writer: &mut Writer) {
+        self.registers.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            registers: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for RegisterStatus {
+    fn save(&self, writer: &mut Writer) {
+        match self {
+            RegisterStatus::None  => writer.tag(0),
+            RegisterStatus::Read  => writer.tag(1),
+            RegisterStatus::Write => writer.tag(2),
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        match reader.tag() {
+            0 => RegisterStatus::None,
+            1 => RegisterStatus::Read,
+            2 => RegisterStatus::Write,
+            _ => panic!(),
+        }
+    }
+}
+
+impl Snapshot for Register {
+    fn save(&self, writer: &mut Writer) {
+        self.status.save(writer);
+        self.pending.save(writer);
+        self.value.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            status: Snapshot::restore(reader),
+            pending: Snapshot::restore(reader),
+            value: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for RegisterId {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self::from_raw(u8::restore(reader)).unwrap()
+    }
+}
+
+impl Snapshot for RegisterRange {
+    fn save(&self, writer: &mut Writer) {
+        self.first.save(writer);
+        self.last.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        let first = RegisterId::restore(reader);
+        let last  = RegisterId::restore(reader);
+        Self::new(first, last).unwrap()
+    }
+}
+
 impl Machine<'_> {
     pub fn register_read(&mut self, register_id: RegisterId) -> u64 {
+        if register_id.is_zero() {
+            return 0;
+        }
+
+        self.race_register(register_id, false);
+        self.register_hazard(register_id, false);
         let register = self.registers.get_mut(register_id);
         if register.status == RegisterStatus::Write {
             self.error_data_race(register_id);
@@ -85,6 +191,16 @@ impl Machine<'_> {
     }
 
     pub fn register_write(&mut self, register_id: RegisterId, value: u64) {
+        if register_id.is_zero() {
+            if self.strict {
+                self.error_zero_register(self.current.unwrap());
+            }
+
+            return;
+        }
+
+        self.race_register(register_id, true);
+        self.register_hazard(register_id, true);
         let register = self.registers.get_mut(register_id);
         if register.status != RegisterStatus::None {
             self.error_data_race(register_id);
@@ -93,4 +209,40 @@ impl Machine<'_> {
         register.status = RegisterStatus::Write;
         register.value = value;
     }
+
+    pub fn register_write_thread(&mut self, thread_id: ThreadId, register_id: RegisterId, value: u64) {
+        let current = self.current.replace(self.origin(thread_id));
+        self.register_write(register_id, value);
+        self.current = current;
+    }
+
+    pub fn register_issue(&mut self, register_id: RegisterId, lock_id: LockId) {
+        if self.hazards.is_none() || register_id.is_zero() {
+            return;
+        }
+
+        self.register_hazard(register_id, true);
+        let origin = self.current.unwrap();
+        self.registers.get_mut(register_id).pending = Some((origin, lock_id));
+    }
+
+    pub fn register_complete(&mut self, register_id: RegisterId, value: u64) {
+        if !register_id.is_zero() {
+            self.registers.get_mut(register_id).pending = None;
+        }
+
+        self.register_write(register_id, value);
+    }
+
+    fn register_hazard(&mut self, register_id: RegisterId, write: bool) {
+        let Some(hazards) = self.hazards else {
+            return;
+        };
+
+        let (Some((other, lock_id)), Some(origin)) = (self.registers.get(register_id).pending, self.current) else {
+            return;
+        };
+
+        self.hazard(hazards, origin, other, register_id, lock_id, write);
+    }
 }
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::fmt::{ Display, Formatter };
+
+use crate::machine::thread::ThreadId;
+
+#[derive(Clone)]
+pub struct Schedule {
+    program: u64,
+    cycles: BTreeMap<usize, Step>,
+}
+
+impl Schedule {
+    pub fn new(program: u64) -> Self {
+        Self {
+            program,
+            cycles: BTreeMap::new(),
+        }
+    }
+
+    pub fn parse(text: &str, program: u64) -> Self {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(fingerprint) = lines.next().and_then(|line| line.strip_prefix("program ")) else {
+            panic!();
+        };
+
+        if u64::from_str_radix(fingerprint, 16).unwrap() != program {
+            panic!();
+        }
+
+        let mut schedule = Self::new(program);
+        for line in lines {
+            let [cycle, threads, callbacks] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+                panic!();
+            };
+
+            let threads = parse_list(threads, |thread| {
+                let Some(thread) = thread.strip_prefix('t') else {
+                    panic!();
+                };
+
+                ThreadId::from_raw(thread.parse().unwrap()).unwrap()
+            });
+
+            let callbacks = parse_list(callbacks, |callback| callback.parse().unwrap());
+            schedule.insert(cycle.parse().unwrap(), threads, callbacks);
+        }
+
+        schedule
+    }
+
+    pub fn insert(&mut self, cycle: usize, threads: Box<[ThreadId]>, callbacks: Box<[usize]>) {
+        self.cycles.insert(cycle, Step { threads, callbacks });
+    }
+
+    pub fn order(&self, cycle: usize, actives: &mut [ThreadId]) -> bool {
+        let Some(step) = self.cycles.get(&cycle) else {
+            return true;
+        };
+
+        if step.threads.len() != actives.len() || !step.threads.iter().all(|thread| actives.contains(thread)) {
+            return false;
+        }
+
+        actives.copy_from_slice(&step.threads);
+        true
+    }
+
+    pub fn permute<T: Clone>(&self, cycle: usize, callbacks: &mut [T]) -> bool {
+        let Some(step) = self.cycles.get(&cycle) else {
+            return true;
+        };
+
+        let mut sorted = step.callbacks.to_vec();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(0 .. callbacks.len()) {
+            return false;
+        }
+
+        let permuted: Box<[T]> = step.callbacks.iter().map(|index| callbacks[*index].clone()).collect();
+        callbacks.clone_from_slice(&permuted);
+        true
+    }
+}
+
+impl Display for Schedule {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(formatter, "program {:016x}", self.program)?;
+        for (cycle, step) in self.cycles.iter() {
+            writeln!(formatter, "{} {} {}", cycle, format_list(&step.threads), format_list(&step.callbacks))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct Step {
+    threads: Box<[ThreadId]>,
+    callbacks: Box<[usize]>,
+}
+
+fn parse_list<T>(list: &str, parse: impl Fn(&str) -> T) -> Box<[T]> {
+    match list {
+        "-" => Box::new([]),
+        _   => list.split(',').map(parse).collect(),
+    }
+}
+
+fn format_list<T: Display>(list: &[T]) -> String {
+    match list {
+        [] => String::from("-"),
+        _  => list.iter().map(T::to_string).collect::<Vec<_>>().join(","),
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt::{ Display, Formatter };
+
+use architecture::SEMAPHORES_COUNT;
+
+use crate::machine::Machine;
+use crate::machine::race::Signal;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
+use crate::machine::thread::ThreadId;
+
+#[derive(Clone, Hash)]
+pub struct Semaphores {
+    semaphores: [Semaphore; SEMAPHORES_COUNT],
+}
+
+impl Semaphores {
+    pub fn new() -> Self {
+        Self {
+            semaphores: [(); SEMAPHORES_COUNT].map(|_| Semaphore::new()),
+        }
+    }
+
+    pub fn get_mut(&mut self, id: SemaphoreId) -> &mut Semaphore {
+        &mut self.semaphores[SemaphoreId::to_raw(id)]
+    }
+}
+
+#[derive(Clone, Hash)]
+pub struct Semaphore {
+    count: u64,
+}
+
+impl Semaphore {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SemaphoreId(u8);
+
+impl SemaphoreId {
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        ((raw as usize) < SEMAPHORES_COUNT).then_some(Self(raw))
+    }
+
+    pub fn to_raw(id: SemaphoreId) -> usize {
+        id.0 as usize
+    }
+}
+
+impl Display for SemaphoreId {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("s")?;
+        formatter.write_fmt(format_args!("{}", self.0))?;
+        Ok(())
+    }
+}
+
+impl Snapshot for Semaphores {
+    fn save(&self, writer: &mut Writer) {
+        self.semaphores.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            semaphores: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for Semaphore {
+    fn save(&self, writer: &mut Writer) {
+        self.count.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            count: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for SemaphoreId {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self::from_raw(u8::restore(reader)).unwrap()
+    }
+}
+
+impl Machine<'_> {
+    pub fn semaphore_init(&mut self, thread_id: ThreadId, semaphore_id: SemaphoreId, mut count: u64) {
+        self.race_signal(thread_id, Signal::Semaphore(semaphore_id));
+        while count > 0 && self.semaphore_release(semaphore_id) {
+            count -= 1;
+        }
+
+        self.semaphores.get_mut(semaphore_id).count = count;
+    }
+
+    pub fn semaphore_up(&mut self, thread_id: ThreadId, semaphore_id: SemaphoreId) {
+        self.race_signal(thread_id, Signal::Semaphore(semaphore_id));
+        if !self.semaphore_release(semaphore_id) {
+            let semaphore = self.semaphores.get_mut(semaphore_id);
+            semaphore.count = semaphore.count.saturating_add(1);
+        }
+    }
+
+    pub fn semaphore_down(&mut self, thread_id: ThreadId, semaphore_id: SemaphoreId) -> bool {
+        let semaphore = self.semaphores.get_mut(semaphore_id);
+        if semaphore.count == 0 {
+            return false;
+        }
+
+        semaphore.count -= 1;
+        self.race_wake(thread_id, Signal::Semaphore(semaphore_id));
+        true
+    }
+
+    fn semaphore_release(&mut self, semaphore_id: SemaphoreId) -> bool {
+        let Some(thread) = self.threads.get_threads().iter().copied()
+            .find(|thread| self.threads.get(*thread).is_waiting_semaphore(semaphore_id)) else {
+            return false;
+        };
+
+        self.race_wake(thread, Signal::Semaphore(semaphore_id));
+        self.threads.get_mut(thread).start();
+        true
+    }
+}
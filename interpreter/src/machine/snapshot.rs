@@ -0,0 +1,254 @@
+use std::collections::VecDeque;
+use std::fs::{ read, write };
+use std::path::Path;
+
+use architecture::Opcode;
+
+use crate::machine::Machine;
+use crate::random::Random;
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"PLISSNAP";
+
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+        }
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn tag(&mut self, tag: u8) {
+        self.bytes.push(tag);
+    }
+}
+
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+        }
+    }
+
+    pub fn bytes(&mut self, length: usize) -> &'a [u8] {
+        let bytes = &self.bytes[self.position .. self.position + length];
+        self.position += length;
+        bytes
+    }
+
+    pub fn tag(&mut self) -> u8 {
+        self.bytes(1)[0]
+    }
+}
+
+pub trait Snapshot: Sized {
+    fn save(&self, writer: &mut Writer);
+    fn restore(reader: &mut Reader) -> Self;
+}
+
+impl Snapshot for u8 {
+    fn save(&self, writer: &mut Writer) {
+        writer.tag(*self);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        reader.tag()
+    }
+}
+
+impl Snapshot for u64 {
+    fn save(&self, writer: &mut Writer) {
+        writer.bytes(&self.to_le_bytes());
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        u64::from_le_bytes(reader.bytes(8).try_into().unwrap())
+    }
+}
+
+impl Snapshot for usize {
+    fn save(&self, writer: &mut Writer) {
+        (*self as u64).save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        u64::restore(reader) as usize
+    }
+}
+
+impl Snapshot for bool {
+    fn save(&self, writer: &mut Writer) {
+        writer.tag(*self as u8);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        match reader.tag() {
+            0 => false,
+            1 => true,
+            _ => panic!(),
+        }
+    }
+}
+
+impl Snapshot for Opcode {
+    fn save(&self, writer: &mut Writer) {
+        writer.tag(Opcode::to_raw(*self));
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Opcode::from_raw(reader.tag()).unwrap()
+    }
+}
+
+impl<T: Snapshot> Snapshot for Option<T> {
+    fn save(&self, writer: &mut Writer) {
+        match self {
+            Some(value) => {
+                writer.tag(1);
+                value.save(writer);
+            },
+            None => writer.tag(0),
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        bool::restore(reader).then(|| T::restore(reader))
+    }
+}
+
+impl<A: Snapshot, B: Snapshot> Snapshot for (A, B) {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+        self.1.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        let a = A::restore(reader);
+        let b = B::restore(reader);
+        (a, b)
+    }
+}
+
+impl<T: Snapshot, const N: usize> Snapshot for [T; N] {
+    fn save(&self, writer: &mut Writer) {
+        for value in self.iter() {
+            value.save(writer);
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        [(); N].map(|_| T::restore(reader))
+    }
+}
+
+impl<T: Snapshot> Snapshot for Box<[T]> {
+    fn save(&self, writer: &mut Writer) {
+        self.len().save(writer);
+        for value in self.iter() {
+            value.save(writer);
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        let length = usize::restore(reader);
+        (0 .. length).map(|_| T::restore(reader)).collect()
+    }
+}
+
+impl<T: Snapshot> Snapshot for Vec<T> {
+    fn save(&self, writer: &mut Writer) {
+        self.len().save(writer);
+        for value in self.iter() {
+            value.save(writer);
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        let length = usize::restore(reader);
+        (0 .. length).map(|_| T::restore(reader)).collect()
+    }
+}
+
+impl<T: Snapshot> Snapshot for VecDeque<T> {
+    fn save(&self, writer: &mut Writer) {
+        self.len().save(writer);
+        for value in self.iter() {
+            value.save(writer);
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        let length = usize::restore(reader);
+        (0 .. length).map(|_| T::restore(reader)).collect()
+    }
+}
+
+impl Snapshot for Random {
+    fn save(&self, writer: &mut Writer) {
+        self.state().save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Random::new(u64::restore(reader))
+    }
+}
+
+impl Machine<'_> {
+    pub fn snapshot_save(&self, path: &Path) {
+        let mut writer = Writer::new();
+        writer.bytes(SNAPSHOT_MAGIC);
+        self.program.fingerprint().save(&mut writer);
+        self.threads.save(&mut writer);
+        self.registers.save(&mut writer);
+        self.locks.save(&mut writer);
+        self.semaphores.save(&mut writer);
+        self.barriers.save(&mut writer);
+        self.channels.save(&mut writer);
+        self.memory.save(&mut writer);
+        self.callbacks.save(&mut writer);
+        self.counter.save(&mut writer);
+        self.elapsed.save(&mut writer);
+        self.random.save(&mut writer);
+        write(path, writer.bytes).unwrap();
+    }
+
+    pub fn snapshot_restore(&mut self, path: &Path) {
+        let bytes = read(path).unwrap();
+        let mut reader = Reader::new(&bytes);
+        if reader.bytes(SNAPSHOT_MAGIC.len()) != SNAPSHOT_MAGIC || u64::restore(&mut reader) != self.program.fingerprint() {
+            panic!();
+        }
+
+        self.threads    = Snapshot::restore(&mut reader);
+        self.registers  = Snapshot::restore(&mut reader);
+        self.locks      = Snapshot::restore(&mut reader);
+        self.semaphores = Snapshot::restore(&mut reader);
+        self.barriers   = Snapshot::restore(&mut reader);
+        self.channels   = Snapshot::restore(&mut reader);
+        self.memory     = Snapshot::restore(&mut reader);
+        self.callbacks  = Snapshot::restore(&mut reader);
+        self.counter    = Snapshot::restore(&mut reader);
+        self.elapsed    = Snapshot::restore(&mut reader);
+
+        let random: Option<Random> = Snapshot::restore(&mut reader);
+        if self.random.is_some() && random.is_some() {
+            self.random = random;
+        }
+
+        if reader.position != bytes.len() {
+            panic!();
+        }
+    }
+}
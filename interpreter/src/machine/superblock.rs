@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use architecture::{ Opcode, THREADS_COUNT };
+
+use crate::machine::Machine;
+use crate::machine::lock::LockId;
+use crate::machine::operation::{ operation_immediate, operation_offset, operation_load, operation_store, operation_unary, operation_calcul };
+use crate::machine::register::RegisterId;
+use crate::machine::thread::ThreadId;
+use crate::program::Program;
+
+type Step = Rc<dyn Fn(&mut Machine, ThreadId)>;
+
+#[derive(Clone)]
+struct Compiled {
+    address: u64,
+    next: u64,
+    step: Step,
+}
+
+type Superblock = Rc<[Compiled]>;
+
+#[derive(Clone)]
+struct Trace {
+    superblock: Superblock,
+    index: usize,
+}
+
+#[derive(Clone)]
+pub struct Superblocks {
+    superblocks: HashMap<u64, Superblock>,
+    traces: [Option<Trace>; THREADS_COUNT],
+}
+
+impl Superblocks {
+    pub fn new() -> Self {
+        Self {
+            superblocks: HashMap::new(),
+            traces: [(); THREADS_COUNT].map(|_| None),
+        }
+    }
+
+    fn next(&mut self, program: &Program, thread_id: ThreadId, cursor: u64) -> Option<(u64, Step)> {
+        let trace = &mut self.traces[ThreadId::to_raw(thread_id)];
+        let followed = trace.as_ref().is_some_and(|trace| {
+            trace.superblock.get(trace.index).is_some_and(|compiled| compiled.address == cursor)
+        });
+
+        if !followed {
+            let superblock = self.superblocks.entry(cursor)
+                .or_insert_with(|| compile(program, cursor))
+                .clone();
+
+            *trace = (!superblock.is_empty()).then_some(Trace { superblock, index: 0 });
+        }
+
+        let trace = trace.as_mut()?;
+        let compiled = &trace.superblock[trace.index];
+        trace.index += 1;
+        Some((compiled.next, compiled.step.clone()))
+    }
+}
+
+impl Machine<'_> {
+    pub fn run_superblock(&mut self, thread_id: ThreadId) -> bool {
+        let cursor = self.threads.get(thread_id).cursor();
+        let Some(superblocks) = self.superblocks.as_mut() else {
+            return false;
+        };
+
+        let Some((next, step)) = superblocks.next(self.program, thread_id, cursor) else {
+            return false;
+        };
+
+        self.threads.get_mut(thread_id).jump(next);
+        step(self, thread_id);
+        true
+    }
+}
+
+fn compile(program: &Program, address: u64) -> Superblock {
+    let mut decoder = Decoder::new(program, address);
+    let mut superblock = Vec::new();
+    loop {
+        let address = decoder.cursor;
+        let Some(step) = decoder.instruction() else {
+            break;
+        };
+
+        superblock.push(Compiled {
+            address,
+            next: decoder.cursor,
+            step,
+        });
+    }
+
+    superblock.into()
+}
+
+struct Decoder<'a> {
+    program: &'a Program,
+    cursor: u64,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(program: &'a Program, cursor: u64) -> Self {
+        Self {
+            program,
+            cursor,
+        }
+    }
+
+    fn instruction(&mut self) -> Option<Step> {
+        let opcode = Opcode::from_raw(self.const8()?)?;
+        if operation_load(opcode).is_some() {
+            let address     = self.register()?;
+            let destination = self.register()?;
+            let lock_id     = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_load(thread_id, address, 0, destination, lock_id, opcode);
+            }));
+        }
+
+        if operation_store(opcode).is_some() {
+            let source      = self.register()?;
+            let destination = self.register()?;
+            let lock_id     = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_store(thread_id, source, destination, 0, lock_id, opcode);
+            }));
+        }
+
+        if let Some(opcode) = operation_offset(opcode).filter(|opcode| operation_load(*opcode).is_some()) {
+            let address     = self.register()?;
+            let offset      = self.immediate(2)?;
+            let destination = self.register()?;
+            let lock_id     = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_load(thread_id, address, offset, destination, lock_id, opcode);
+            }));
+        }
+
+        if let Some(opcode) = operation_offset(opcode).filter(|opcode| operation_store(*opcode).is_some()) {
+            let source      = self.register()?;
+            let destination = self.register()?;
+            let offset      = self.immediate(2)?;
+            let lock_id     = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_store(thread_id, source, destination, offset, lock_id, opcode);
+            }));
+        }
+
+        if let Some((opcode, size)) = operation_immediate(opcode) {
+            operation_calcul(opcode)?;
+            let a         = self.register()?;
+            let immediate = self.immediate(size)?;
+            let result    = self.register()?;
+            let lock_id   = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_calcul_immediate(thread_id, a, immediate, result, lock_id, opcode);
+            }));
+        }
+
+        if operation_unary(opcode).is_some() {
+            let a       = self.register()?;
+            let result  = self.register()?;
+            let lock_id = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_unary(thread_id, a, result, lock_id, opcode);
+            }));
+        }
+
+        if operation_calcul(opcode).is_some() {
+            let a       = self.register()?;
+            let b       = self.register()?;
+            let result  = self.register()?;
+            let lock_id = self.lock()?;
+            return Some(Rc::new(move |machine, thread_id| {
+                machine.issue_calcul(thread_id, a, b, result, lock_id, opcode);
+            }));
+        }
+
+        Some(match opcode {
+            Opcode::Nop => Rc::new(|_, _| {}),
+            Opcode::Move => {
+                let source      = self.register()?;
+                let destination = self.register()?;
+                Rc::new(move |machine, _| {
+                    let value = machine.register_read(source);
+                    machine.register_write(destination, value);
+                })
+            },
+            Opcode::Const8  => self.constant(|decoder| decoder.const8().map(u64::from))?,
+            Opcode::Const16 => self.constant(|decoder| decoder.const16().map(u64::from))?,
+            Opcode::Const32 => self.constant(|decoder| decoder.const32().map(u64::from))?,
+            Opcode::Const64 => self.constant(|decoder| decoder.const64())?,
+            Opcode::BitExtract => {
+                let a        = self.register()?;
+                let position = self.const8()?;
+                let width    = self.const8()?;
+                let result   = self.register()?;
+                let lock_id  = self.lock()?;
+                Rc::new(move |machine, thread_id| {
+                    machine.issue_bit_extract(thread_id, a, position, width, result, lock_id);
+                })
+            },
+            Opcode::BitInsert => {
+                let a        = self.register()?;
+                let b        = self.register()?;
+                let position = self.const8()?;
+                let width    = self.const8()?;
+                let result   = self.register()?;
+                let lock_id  = self.lock()?;
+                Rc::new(move |machine, thread_id| {
+                    machine.issue_bit_insert(thread_id, a, b, position, width, result, lock_id);
+                })
+            },
+            Opcode::Push => {
+                let source  = self.register()?;
+                let lock_id = self.lock()?;
+                Rc::new(move |machine, thread_id| {
+                    machine.issue_push(thread_id, source, lock_id);
+                })
+            },
+            Opcode::Pop => {
+                let destination = self.register()?;
+                let lock_id     = self.lock()?;
+                Rc::new(move |machine, thread_id| {
+                    machine.issue_pop(thread_id, destination, lock_id);
+                })
+            },
+            Opcode::Select => {
+                let condition = self.register()?;
+                let a         = self.register()?;
+                let b         = self.register()?;
+                let result    = self.register()?;
+                let lock_id   = self.lock()?;
+                Rc::new(move |machine, thread_id| {
+                    machine.issue_select(thread_id, condition, a, b, result, lock_id);
+                })
+            },
+            _ => return None,
+        })
+    }
+
+    fn constant(&mut self, closure: impl Fn(&mut Self) -> Option<u64>) -> Option<Step> {
+        let register = self.register()?;
+        let constant = closure(self)?;
+        Some(Rc::new(move |machine, _| {
+            machine.register_write(register, constant);
+        }))
+    }
+
+    fn immediate(&mut self, size: usize) -> Option<u64> {
+        Some(match size {
+            1 => self.const8()? as i8 as u64,
+            2 => self.const16()? as i16 as u64,
+            _ => self.const32()? as i32 as u64,
+        })
+    }
+
+    fn register(&mut self) -> Option<RegisterId> {
+        RegisterId::from_raw(self.const8()?)
+    }
+
+    fn lock(&mut self) -> Option<LockId> {
+        LockId::from_raw(self.const8()?)
+    }
+
+    fn const8(&mut self) -> Option<u8> {
+        let value = self.program.get_8(self.cursor)?;
+        self.cursor += 1;
+        Some(value)
+    }
+
+    fn const16(&mut self) -> Option<u16> {
+        let value = self.program.get_16(self.cursor)?;
+        self.cursor += 2;
+        Some(value)
+    }
+
+    fn const32(&mut self) -> Option<u32> {
+        let value = self.program.get_32(self.cursor)?;
+        self.cursor += 4;
+        Some(value)
+    }
+
+    fn const64(&mut self) -> Option<u64> {
+        let value = self.program.get_64(self.cursor)?;
+        self.cursor += 8;
+        Some(value)
+    }
+}
@@ -2,10 +2,15 @@ use std::fmt::{ Display, Formatter };
 
 use architecture::{ Opcode, THREADS_COUNT };
 
-use crate::machine::lock::LockId;
+use crate::machine::barrier::BarrierId;
+use crate::machine::channel::ChannelId;
+use crate::machine::lock::{ LockId, LockMask, lock_mask };
 use crate::machine::Machine;
-use crate::machine::register::RegisterId;
+use crate::machine::register::{ RegisterId, RegisterRange };
+use crate::machine::semaphore::SemaphoreId;
+use crate::machine::snapshot::{ Reader, Snapshot, Writer };
 
+#[derive(Clone, Hash)]
 pub struct Threads {
     threads: Box<[Thread]>,
 }
@@ -39,6 +44,12 @@ impl Threads {
             .collect()
     }
 
+    pub fn get_inactive(&self) -> Option<ThreadId> {
+        self.threads.iter()
+            .find(|thread| thread.is_inactive())
+            .map(|thread| thread.id)
+    }
+
     pub fn get_actives(&self) -> Box<[ThreadId]> {
         self.threads.iter()
             .filter(|thread| thread.is_active())
@@ -47,17 +58,48 @@ impl Threads {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ThreadStatus {
     Active,
     Inactive,
-    Waiting(LockId),
+    Waiting(u64),
+    WaitingAny(u64, RegisterId),
+    WaitingSemaphore(SemaphoreId),
+    WaitingBarrier(BarrierId),
+    WaitingSend(ChannelId, u64),
+    WaitingRecv(ChannelId, RegisterId),
+    WaitingJoin(ThreadId),
+    Stalled,
+}
+
+impl Display for ThreadStatus {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ThreadStatus::Active               => formatter.write_str("active"),
+            ThreadStatus::Inactive             => formatter.write_str("inactive"),
+            ThreadStatus::Waiting(mask)
+                if mask.count_ones() == 1      => formatter.write_fmt(format_args!("waiting for lock {}", LockMask(mask))),
+            ThreadStatus::Waiting(mask)        => formatter.write_fmt(format_args!("waiting for all of the locks {}", LockMask(mask))),
+            ThreadStatus::WaitingAny(mask, _)  => formatter.write_fmt(format_args!("waiting for any of the locks {}", LockMask(mask))),
+            ThreadStatus::WaitingSemaphore(id) => formatter.write_fmt(format_args!("waiting for semaphore `{}`", id)),
+            ThreadStatus::WaitingBarrier(id)   => formatter.write_fmt(format_args!("waiting for barrier `{}`", id)),
+            ThreadStatus::WaitingSend(id, _)   => formatter.write_fmt(format_args!("waiting to send on channel `{}`", id)),
+            ThreadStatus::WaitingRecv(id, _)   => formatter.write_fmt(format_args!("waiting to receive on channel `{}`", id)),
+            ThreadStatus::WaitingJoin(id)      => formatter.write_fmt(format_args!("waiting for thread `{}`", id)),
+            ThreadStatus::Stalled              => formatter.write_str("stalled"),
+        }
+    }
 }
 
+#[derive(Clone, Hash)]
 pub struct ThreadProfile {
     active: usize,
     inactive: usize,
     waiting: usize,
+    waiting_semaphore: usize,
+    waiting_barrier: usize,
+    waiting_channel: usize,
+    stalled: usize,
 }
 
 impl ThreadProfile {
@@ -66,6 +108,10 @@ impl ThreadProfile {
             active: 0,
             inactive: 0,
             waiting: 0,
+            waiting_semaphore: 0,
+            waiting_barrier: 0,
+            waiting_channel: 0,
+            stalled: 0,
         }
     }
 
@@ -80,12 +126,31 @@ impl ThreadProfile {
     pub fn waiting(&self) -> usize {
         self.waiting
     }
+
+    pub fn waiting_semaphore(&self) -> usize {
+        self.waiting_semaphore
+    }
+
+    pub fn waiting_barrier(&self) -> usize {
+        self.waiting_barrier
+    }
+
+    pub fn waiting_channel(&self) -> usize {
+        self.waiting_channel
+    }
+
+    pub fn stalled(&self) -> usize {
+        self.stalled
+    }
 }
 
+#[derive(Clone, Hash)]
 pub struct Thread {
     id: ThreadId,
     cursor: u64,
+    instruction: u64,
     active: ThreadStatus,
+    pending: usize,
     profile: ThreadProfile,
 }
 
@@ -94,7 +159,9 @@ impl Thread {
         Self {
             id,
             cursor: 0,
+            instruction: 0,
             active: ThreadStatus::Inactive,
+            pending: 0,
             profile: ThreadProfile::new(),
         }
     }
@@ -102,9 +169,16 @@ impl Thread {
 
     pub fn profile_update(&mut self) {
         match self.active {
-            ThreadStatus::Active     => self.profile.active   += 1,
-            ThreadStatus::Inactive   => self.profile.inactive += 1,
-            ThreadStatus::Waiting(_) => self.profile.waiting  += 1,
+            ThreadStatus::Active              => self.profile.active            += 1,
+            ThreadStatus::Inactive            => self.profile.inactive          += 1,
+            ThreadStatus::Waiting(_)          => self.profile.waiting           += 1,
+            ThreadStatus::WaitingAny(_, _)    => self.profile.waiting           += 1,
+            ThreadStatus::WaitingSemaphore(_) => self.profile.waiting_semaphore += 1,
+            ThreadStatus::WaitingBarrier(_)   => self.profile.waiting_barrier   += 1,
+            ThreadStatus::WaitingSend(_, _)   => self.profile.waiting_channel   += 1,
+            ThreadStatus::WaitingRecv(_, _)   => self.profile.waiting_channel   += 1,
+            ThreadStatus::WaitingJoin(_)      => self.profile.waiting           += 1,
+            ThreadStatus::Stalled             => self.profile.stalled           += 1,
         }
     }
 
@@ -116,14 +190,71 @@ impl Thread {
         self.active == ThreadStatus::Active
     }
 
-    pub fn is_waiting(&self, other: LockId) -> bool {
-        if let ThreadStatus::Waiting(lock) = self.active {
-            lock == other
-        } else {
-            false
+    pub fn notify(&mut self, lock: LockId) -> Option<RegisterId> {
+        let bit = lock_mask(lock);
+        match &mut self.active {
+            ThreadStatus::Waiting(mask) if *mask & bit != 0 => {
+                *mask &= !bit;
+                if *mask == 0 {
+                    self.start();
+                }
+
+                None
+            },
+            ThreadStatus::WaitingAny(mask, destination) if *mask & bit != 0 => {
+                let destination = *destination;
+                self.start();
+                Some(destination)
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_waiting(&self, lock: LockId) -> bool {
+        match self.active {
+            ThreadStatus::Waiting(mask) | ThreadStatus::WaitingAny(mask, _) => mask & lock_mask(lock) != 0,
+            _ => false,
+        }
+    }
+
+    pub fn is_inactive(&self) -> bool {
+        self.active == ThreadStatus::Inactive
+    }
+
+    pub fn is_stalled(&self) -> bool {
+        self.active == ThreadStatus::Stalled
+    }
+
+    pub fn is_joining(&self, other: ThreadId) -> bool {
+        self.active == ThreadStatus::WaitingJoin(other)
+    }
+
+    pub fn is_waiting_semaphore(&self, other: SemaphoreId) -> bool {
+        self.active == ThreadStatus::WaitingSemaphore(other)
+    }
+
+    pub fn is_waiting_barrier(&self, other: BarrierId) -> bool {
+        self.active == ThreadStatus::WaitingBarrier(other)
+    }
+
+    pub fn waiting_send(&self, other: ChannelId) -> Option<u64> {
+        match self.active {
+            ThreadStatus::WaitingSend(channel, value) if channel == other => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn waiting_recv(&self, other: ChannelId) -> Option<RegisterId> {
+        match self.active {
+            ThreadStatus::WaitingRecv(channel, destination) if channel == other => Some(destination),
+            _ => None,
         }
     }
 
+    pub fn status(&self) -> ThreadStatus {
+        self.active
+    }
+
     pub fn id(&self) -> ThreadId {
         self.id
     }
@@ -132,14 +263,30 @@ impl Thread {
         self.cursor
     }
 
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
     pub fn profile(&self) -> &ThreadProfile {
         &self.profile
     }
 
+    pub fn fetch(&mut self) {
+        self.instruction = self.cursor;
+    }
+
     pub fn jump(&mut self, cursor: u64) {
         self.cursor = cursor;
     }
 
+    pub fn issue(&mut self) {
+        self.pending += 1;
+    }
+
+    pub fn complete(&mut self) {
+        self.pending -= 1;
+    }
+
     pub fn start(&mut self) {
         self.active = ThreadStatus::Active;
     }
@@ -149,11 +296,43 @@ impl Thread {
     }
 
     pub fn wait(&mut self, lock: LockId) {
-        self.active = ThreadStatus::Waiting(lock);
+        self.active = ThreadStatus::Waiting(lock_mask(lock));
+    }
+
+    pub fn wait_all(&mut self, mask: u64) {
+        self.active = ThreadStatus::Waiting(mask);
+    }
+
+    pub fn wait_any(&mut self, mask: u64, destination: RegisterId) {
+        self.active = ThreadStatus::WaitingAny(mask, destination);
+    }
+
+    pub fn wait_semaphore(&mut self, semaphore: SemaphoreId) {
+        self.active = ThreadStatus::WaitingSemaphore(semaphore);
+    }
+
+    pub fn wait_barrier(&mut self, barrier: BarrierId) {
+        self.active = ThreadStatus::WaitingBarrier(barrier);
+    }
+
+    pub fn stall(&mut self) {
+        self.active = ThreadStatus::Stalled;
+    }
+
+    pub fn join(&mut self, thread: ThreadId) {
+        self.active = ThreadStatus::WaitingJoin(thread);
+    }
+
+    pub fn wait_send(&mut self, channel: ChannelId, value: u64) {
+        self.active = ThreadStatus::WaitingSend(channel, value);
+    }
+
+    pub fn wait_recv(&mut self, channel: ChannelId, destination: RegisterId) {
+        self.active = ThreadStatus::WaitingRecv(channel, destination);
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ThreadId(u8);
 
 impl ThreadId {
@@ -174,6 +353,204 @@ impl Display for ThreadId {
     }
 }
 
+#[derive(Clone, Copy, Hash)]
+pub struct Origin {
+    thread_id: ThreadId,
+    address: u64,
+}
+
+impl Origin {
+    pub fn thread_id(self) -> ThreadId {
+        self.thread_id
+    }
+
+    pub fn address(self) -> u64 {
+        self.address
+    }
+}
+
+impl Snapshot for Threads {
+    fn save(&self, writer: &mut Writer) {
+        self.threads.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            threads: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for ThreadStatus {
+    fn save(&self, writer: &mut Writer) {
+        match self {
+            ThreadStatus::Active => writer.tag(0),
+            ThreadStatus::Inactive => writer.tag(1),
+            ThreadStatus::Waiting(mask) => {
+                writer.tag(2);
+                mask.save(writer);
+            },
+            ThreadStatus::WaitingAny(mask, destination) => {
+                writer.tag(3);
+                mask.save(writer);
+                destination.save(writer);
+            },
+            ThreadStatus::WaitingSemaphore(semaphore_id) => {
+                writer.tag(4);
+                semaphore_id.save(writer);
+            },
+            ThreadStatus::WaitingBarrier(barrier_id) => {
+                writer.tag(5);
+                barrier_id.save(writer);
+            },
+            ThreadStatus::WaitingSend(channel_id, value) => {
+                writer.tag(6);
+                channel_id.save(writer);
+                value.save(writer);
+            },
+            ThreadStatus::WaitingRecv(channel_id, destination) => {
+                writer.tag(7);
+                channel_id.save(writer);
+                destination.save(writer);
+            },
+            ThreadStatus::WaitingJoin(thread_id) => {
+                writer.tag(8);
+                thread_id.save(writer);
+            },
+            ThreadStatus::Stalled => writer.tag(9),
+        }
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        match reader.tag() {
+            0 => ThreadStatus::Active,
+            1 => ThreadStatus::Inactive,
+            2 => ThreadStatus::Waiting(Snapshot::restore(reader)),
+            3 => ThreadStatus::WaitingAny(Snapshot::restore(reader), Snapshot::restore(reader)),
+            4 => ThreadStatus::WaitingSemaphore(Snapshot::restore(reader)),
+            5 => ThreadStatus::WaitingBarrier(Snapshot::restore(reader)),
+            6 => ThreadStatus::WaitingSend(Snapshot::restore(reader), Snapshot::restore(reader)),
+            7 => ThreadStatus::WaitingRecv(Snapshot::restore(reader), Snapshot::restore(reader)),
+            8 => ThreadStatus::WaitingJoin(Snapshot::restore(reader)),
+            9 => ThreadStatus::Stalled,
+            _ => panic!(),
+        }
+    }
+}
+
+impl Snapshot for ThreadProfile {
+    fn save(&self, writer: &mut Writer) {
+        self.active.save(writer);
+        self.inactive.save(writer);
+        self.waiting.save(writer);
+        self.waiting_semaphore.save(writer);
+        self.waiting_barrier.save(writer);
+        self.waiting_channel.save(writer);
+        self.stalled.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            active: Snapshot::restore(reader),
+            inactive: Snapshot::restore(reader),
+            waiting: Snapshot::restore(reader),
+            waiting_semaphore: Snapshot::restore(reader),
+            waiting_barrier: Snapshot::restore(reader),
+            waiting_channel: Snapshot::restore(reader),
+            stalled: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for Thread {
+    fn save(&self, writer: &mut Writer) {
+        self.id.save(writer);
+        self.cursor.save(writer);
+        self.instruction.save(writer);
+        self.active.save(writer);
+        self.pending.save(writer);
+        self.profile.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            id: Snapshot::restore(reader),
+            cursor: Snapshot::restore(reader),
+            instruction: Snapshot::restore(reader),
+            active: Snapshot::restore(reader),
+            pending: Snapshot::restore(reader),
+            profile: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Snapshot for ThreadId {
+    fn save(&self, writer: &mut Writer) {
+        self.0.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self::from_raw(u8::restore(reader)).unwrap()
+    }
+}
+
+impl Snapshot for Origin {
+    fn save(&self, writer: &mut Writer) {
+        self.thread_id.save(writer);
+        self.address.save(writer);
+    }
+
+    fn restore(reader: &mut Reader) -> Self {
+        Self {
+            thread_id: Snapshot::restore(reader),
+            address: Snapshot::restore(reader),
+        }
+    }
+}
+
+impl Machine<'_> {
+    pub fn thread_start(&mut self, thread_id: ThreadId, address: u64) {
+        self.race_fork(thread_id);
+        self.stack_reset(thread_id);
+        let thread = self.threads.get_mut(thread_id);
+        thread.jump(address);
+        thread.start();
+    }
+
+    pub fn thread_stop(&mut self, thread_id: ThreadId) {
+        self.threads.get_mut(thread_id).stop();
+        for thread in self.threads.get_threads().iter().copied() {
+            if self.threads.get(thread).is_joining(thread_id) {
+                self.race_join(thread, thread_id);
+                self.threads.get_mut(thread).start();
+            }
+        }
+    }
+
+    pub fn thread_start_checked(&mut self, origin: Origin, thread_id: ThreadId, address: u64) {
+        if self.strict && !self.threads.get(thread_id).is_inactive() {
+            self.error_thread_active(origin, thread_id);
+        }
+
+        self.thread_start(thread_id, address);
+    }
+
+    pub fn thread_stop_checked(&mut self, origin: Origin, thread_id: ThreadId) {
+        if self.strict && self.threads.get(thread_id).pending() != 0 {
+            self.error_thread_pending(origin, thread_id);
+        }
+
+        self.thread_stop(thread_id);
+    }
+
+    pub fn origin(&self, thread_id: ThreadId) -> Origin {
+        Origin {
+            thread_id,
+            address: self.threads.get(thread_id).instruction,
+        }
+    }
+}
+
 impl Machine<'_> {
     pub fn get_8(&mut self, thread_id: ThreadId) -> u8 {
         let cursor = self.threads.get(thread_id).cursor;
@@ -215,11 +592,32 @@ impl Machine<'_> {
         RegisterId::from_raw(value).unwrap_or_else(|| self.error_invalid_register(thread_id, value))
     }
 
+    pub fn next_register_range(&mut self, thread_id: ThreadId) -> RegisterRange {
+        let first = self.next_register(thread_id);
+        let last  = self.next_register(thread_id);
+        RegisterRange::new(first, last).unwrap_or_else(|| self.error_invalid_register_range(thread_id, first, last))
+    }
+
     pub fn next_lock(&mut self, thread_id: ThreadId) -> LockId {
         let value = self.get_8(thread_id);
         LockId::from_raw(value).unwrap_or_else(|| self.error_invalid_lock(thread_id, value))
     }
 
+    pub fn next_semaphore(&mut self, thread_id: ThreadId) -> SemaphoreId {
+        let value = self.get_8(thread_id);
+        SemaphoreId::from_raw(value).unwrap_or_else(|| self.error_invalid_semaphore(thread_id, value))
+    }
+
+    pub fn next_barrier(&mut self, thread_id: ThreadId) -> BarrierId {
+        let value = self.get_8(thread_id);
+        BarrierId::from_raw(value).unwrap_or_else(|| self.error_invalid_barrier(thread_id, value))
+    }
+
+    pub fn next_channel(&mut self, thread_id: ThreadId) -> ChannelId {
+        let value = self.get_8(thread_id);
+        ChannelId::from_raw(value).unwrap_or_else(|| self.error_invalid_channel(thread_id, value))
+    }
+
     pub fn next_thread(&mut self, thread_id: ThreadId) -> ThreadId {
         let value = self.get_8(thread_id);
         ThreadId::from_raw(value).unwrap_or_else(|| self.error_invalid_thread(thread_id, value))
@@ -240,4 +638,12 @@ impl Machine<'_> {
     pub fn next_const64(&mut self, thread_id: ThreadId) -> u64 {
         self.get_64(thread_id) as u64
     }
+
+    pub fn next_immediate(&mut self, thread_id: ThreadId, size: usize) -> u64 {
+        match size {
+            1 => self.get_8(thread_id) as i8 as u64,
+            2 => self.get_16(thread_id) as i16 as u64,
+            _ => self.get_32(thread_id) as i32 as u64,
+        }
+    }
 }
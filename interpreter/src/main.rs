@@ -2,7 +2,10 @@
 #![feature(let_else)]
 
 mod machine;
+mod options;
 mod program;
+mod random;
+mod stress;
 mod time;
 
 use std::fs::read;
@@ -10,17 +13,26 @@ use std::env::args;
 use std::path::Path;
 
 use machine::Machine;
+use options::Options;
 use program::Program;
+use stress::stress;
 
 fn main() {
-    let arguments = args().collect::<Box<[_]>>();
-    if arguments.len() != 2 {
-        panic!();
+    let arguments = Options::normalize(&args().skip(1).collect::<Box<[_]>>());
+    let options = Options::parse(&arguments);
+    if let Some(runs) = options.runs {
+        stress(&arguments, options.seed.unwrap_or(0), runs);
+        return;
     }
 
-    let input = get_input_path(&arguments[1]);
+    let input = get_input_path(&options.input);
     let program = Program::new(read(input).unwrap().into_boxed_slice());
-    let mut machine = Machine::new(&program);
+    let mut machine = Machine::new(&program, &options);
+    if let Some(bound) = options.check {
+        machine.check(bound, &input.with_extension("schedule"));
+        return;
+    }
+
     machine.run();
 }
 
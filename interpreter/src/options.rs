@@ -0,0 +1,121 @@
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Hazards {
+    Warning,
+    Error,
+}
+
+pub struct Options {
+    pub input: Box<str>,
+    pub superblocks: bool,
+    pub strict: bool,
+    pub races: bool,
+    pub hazards: Option<Hazards>,
+    pub seed: Option<u64>,
+    pub jitter: usize,
+    pub runs: Option<u64>,
+    pub check: Option<usize>,
+    pub schedule: Option<Box<str>>,
+    pub save_at: Option<usize>,
+    pub save_to: Option<Box<str>>,
+    pub restore: Option<Box<str>>,
+}
+
+const VALUE_OPTIONS: [&str; 9] = ["--hazards", "--seed", "--jitter", "--runs", "--check", "--schedule", "--save-at", "--save-to", "--restore"];
+
+impl Options {
+    pub fn normalize(arguments: &[String]) -> Box<[String]> {
+        let mut normalized = Vec::new();
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            if !VALUE_OPTIONS.contains(&argument.as_str()) {
+                normalized.push(argument.clone());
+                continue;
+            }
+
+            let Some(value) = arguments.next() else {
+                panic!();
+            };
+
+            normalized.push(format!("{}={}", argument, value));
+        }
+
+        normalized.into_boxed_slice()
+    }
+
+    pub fn parse(arguments: &[String]) -> Self {
+        let mut input = None;
+        let mut superblocks = false;
+        let mut strict = false;
+        let mut races = false;
+        let mut hazards = None;
+        let mut seed = None;
+        let mut jitter = 0;
+        let mut runs = None;
+        let mut check = None;
+        let mut schedule = None;
+        let mut save_at = None;
+        let mut save_to = None;
+        let mut restore = None;
+        for argument in arguments.iter() {
+            match argument.as_str() {
+                "--superblocks"     => superblocks = true,
+                "--strict"          => strict = true,
+                "--races"           => races = true,
+                "--hazards=warning" => hazards = Some(Hazards::Warning),
+                "--hazards=error"   => hazards = Some(Hazards::Error),
+                _ if argument.starts_with("--seed=")     => seed = Some(parse_value(argument)),
+                _ if argument.starts_with("--jitter=")   => jitter = parse_value(argument) as usize,
+                _ if argument.starts_with("--runs=")     => runs = Some(parse_value(argument)),
+                _ if argument.starts_with("--check=")    => check = Some(parse_value(argument) as usize),
+                _ if argument.starts_with("--schedule=") => schedule = argument.split_once('=').map(|(_, path)| Box::from(path)),
+                _ if argument.starts_with("--save-at=")  => save_at = Some(parse_value(argument) as usize),
+                _ if argument.starts_with("--save-to=")  => save_to = argument.split_once('=').map(|(_, path)| Box::from(path)),
+                _ if argument.starts_with("--restore=")  => restore = argument.split_once('=').map(|(_, path)| Box::from(path)),
+                _ if argument.starts_with("--") => panic!(),
+                _ => {
+                    if input.is_some() {
+                        panic!();
+                    }
+
+                    input = Some(Box::from(argument.as_str()));
+                },
+            }
+        }
+
+        let Some(input) = input else {
+            panic!();
+        };
+
+        if jitter != 0 && seed.is_none() && runs.is_none() {
+            panic!();
+        }
+
+        if check.is_some() && (seed.is_some() || jitter != 0 || schedule.is_some() || superblocks || save_at.is_some()) {
+            panic!();
+        }
+
+        Self {
+            input,
+            superblocks,
+            strict,
+            races,
+            hazards,
+            seed,
+            jitter,
+            runs,
+            check,
+            schedule,
+            save_at,
+            save_to,
+            restore,
+        }
+    }
+}
+
+fn parse_value(argument: &str) -> u64 {
+    let Some((_, value)) = argument.split_once('=') else {
+        panic!();
+    };
+
+    value.parse().unwrap()
+}
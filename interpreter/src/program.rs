@@ -9,6 +9,10 @@ impl Program {
         }
     }
 
+    pub fn fingerprint(&self) -> u64 {
+        self.program.iter().fold(0xCBF29CE484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001B3))
+    }
+
     pub fn get(&self, cursor: u64) -> Option<u8> {
         self.program.get(cursor as usize).copied()
     }
@@ -0,0 +1,65 @@
+use std::env::current_exe;
+use std::io::{ Read, Write, stdin };
+use std::process::{ Command, Output, Stdio, exit };
+
+pub fn stress(arguments: &[String], seed: u64, runs: u64) {
+    let arguments: Box<[&String]> = arguments.iter()
+        .filter(|argument| !argument.starts_with("--runs=") && !argument.starts_with("--seed="))
+        .collect();
+
+    let deterministic: Box<[&String]> = arguments.iter()
+        .copied()
+        .filter(|argument| !argument.starts_with("--jitter="))
+        .collect();
+
+    let mut input = Vec::new();
+    stdin().read_to_end(&mut input).unwrap();
+
+    let reference = run(&deterministic, None, &input);
+    let mut failures = 0;
+    for seed in seed .. seed + runs {
+        let output = run(&arguments, Some(seed), &input);
+        if normalize(&output.stderr) != normalize(&reference.stderr) {
+            failures += 1;
+            let error = String::from_utf8_lossy(&output.stderr);
+            println!("Seed {}: {}", seed, error.lines().next().unwrap_or("different errors."));
+        } else if output.stdout != reference.stdout {
+            failures += 1;
+            println!("Seed {}: different output.", seed);
+        }
+    }
+
+    println!("{} of {} runs differ from the deterministic run.", failures, runs);
+    if failures != 0 {
+        exit(1);
+    }
+}
+
+fn run(arguments: &[&String], seed: Option<u64>, input: &[u8]) -> Output {
+    let mut command = Command::new(current_exe().unwrap());
+    command.args(arguments);
+    if let Some(seed) = seed {
+        command.arg(format!("--seed={}", seed));
+    }
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn normalize(output: &[u8]) -> String {
+    String::from_utf8_lossy(output)
+        .lines()
+        .map(|line| match (line.find("(cycle "), line.find("): ")) {
+            (Some(start), Some(end)) if start < end => format!("{}{}", &line[.. start], &line[end + 1 ..]),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,15 +1,66 @@
-pub const TIME_LOAD:  usize = 200;
-pub const TIME_STORE: usize = 200;
-pub const TIME_AND:   usize = 2;
-pub const TIME_OR:    usize = 2;
-pub const TIME_XOR:   usize = 2;
-pub const TIME_SHL:   usize = 2;
-pub const TIME_SHR:   usize = 2;
-pub const TIME_ADD:   usize = 5;
-pub const TIME_SUB:   usize = 5;
-pub const TIME_MUL:   usize = 20;
-pub const TIME_DIV:   usize = 50;
-pub const TIME_REM:   usize = 50;
-pub const TIME_EQ:    usize = 2;
-pub const TIME_LT:    usize = 5;
-pub const TIME_GT:    usize = 2;
+pub const TIME_LOAD:    usize = 200;
+pub const TIME_LOAD8S:  usize = 200;
+pub const TIME_LOAD16S: usize = 200;
+pub const TIME_LOAD32S: usize = 200;
+pub const TIME_STORE:   usize = 200;
+pub const TIME_PUSH:    usize = 200;
+pub const TIME_POP:     usize = 200;
+pub const TIME_CALL:    usize = 200;
+pub const TIME_RET:     usize = 200;
+pub const TIME_AND:     usize = 2;
+pub const TIME_OR:      usize = 2;
+pub const TIME_XOR:     usize = 2;
+pub const TIME_NOT:     usize = 2;
+pub const TIME_ANDN:    usize = 2;
+pub const TIME_ROTL:    usize = 2;
+pub const TIME_ROTR:    usize = 2;
+pub const TIME_POPCNT:  usize = 3;
+pub const TIME_CLZ:     usize = 3;
+pub const TIME_CTZ:     usize = 3;
+pub const TIME_BSWAP:   usize = 2;
+pub const TIME_BFX:     usize = 2;
+pub const TIME_BFI:     usize = 3;
+pub const TIME_SHL:     usize = 2;
+pub const TIME_SHR:     usize = 2;
+pub const TIME_SRA:     usize = 2;
+pub const TIME_ADD:     usize = 5;
+pub const TIME_SUB:     usize = 5;
+pub const TIME_MUL:     usize = 20;
+pub const TIME_ADDC:    usize = 5;
+pub const TIME_SUBC:    usize = 5;
+pub const TIME_MULC:    usize = 20;
+pub const TIME_MULH:    usize = 20;
+pub const TIME_DIV:     usize = 50;
+pub const TIME_REM:     usize = 50;
+pub const TIME_SDIV:    usize = 55;
+pub const TIME_SREM:    usize = 55;
+pub const TIME_EQ:      usize = 2;
+pub const TIME_LT:      usize = 5;
+pub const TIME_GT:      usize = 2;
+pub const TIME_NE:      usize = 2;
+pub const TIME_LE:      usize = 5;
+pub const TIME_GE:      usize = 2;
+pub const TIME_SLT:     usize = 5;
+pub const TIME_SGT:     usize = 2;
+pub const TIME_SELECT:  usize = 2;
+pub const TIME_FADD:    usize = 10;
+pub const TIME_FSUB:    usize = 10;
+pub const TIME_FMUL:    usize = 25;
+pub const TIME_FDIV:    usize = 60;
+pub const TIME_FSQRT:   usize = 80;
+pub const TIME_FMIN:    usize = 5;
+pub const TIME_FMAX:    usize = 5;
+pub const TIME_FEQ:     usize = 3;
+pub const TIME_FLT:     usize = 3;
+pub const TIME_FGT:     usize = 3;
+pub const TIME_FLE:     usize = 3;
+pub const TIME_FGE:     usize = 3;
+pub const TIME_ITOF:    usize = 8;
+pub const TIME_FTOI:    usize = 8;
+pub const TIME_VLOAD:   usize = 200;
+pub const TIME_VSTORE:  usize = 200;
+pub const TIME_CAS:     usize = 250;
+pub const TIME_XCHG:    usize = 250;
+pub const TIME_FETCHADD: usize = 250;
+pub const TIME_FETCHAND: usize = 250;
+pub const TIME_FETCHOR: usize = 250;